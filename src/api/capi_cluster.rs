@@ -1,10 +1,13 @@
 use std::collections::BTreeMap;
 
-use cluster_api_rs::capi_cluster::{ClusterSpec, ClusterStatus};
+use chrono::{SecondsFormat, Utc};
+use cluster_api_rs::capi_machine::Machine;
 use fleet_api_rs::{
     fleet_bundle_namespace_mapping::BundleNamespaceMappingNamespaceSelector,
+    fleet_cluster::{ClusterAgentEnvVars, ClusterAgentTolerations},
     fleet_clustergroup::{ClusterGroupSelector, ClusterGroupSpec},
 };
+use k8s_openapi::{api::core::v1::ObjectReference, apimachinery::pkg::apis::meta::v1::Condition};
 use kube::{
     api::{ObjectMeta, TypeMeta},
     Resource, ResourceExt as _,
@@ -12,17 +15,51 @@ use kube::{
 #[cfg(feature = "agent-initiated")]
 use rand::distr::{Alphanumeric, SampleString as _};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 
 use super::{
     bundle_namespace_mapping::BundleNamespaceMapping,
-    fleet_addon_config::ClusterConfig,
+    fleet_addon_config::{
+        truncate_name, ClusterConfig, NamingError, ReadinessGate, MAX_NAME_LENGTH,
+    },
     fleet_cluster,
-    fleet_clustergroup::{ClusterGroup, CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL},
+    fleet_clustergroup::{
+        ClusterGroup, CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL, CONTROL_PLANE_KIND_LABEL,
+        INFRASTRUCTURE_KIND_LABEL, KUBERNETES_MINOR_VERSION_LABEL,
+        KUBERNETES_OBSERVED_VERSION_LABEL, KUBERNETES_VERSION_LABEL,
+    },
 };
 
 #[cfg(feature = "agent-initiated")]
 use super::fleet_cluster_registration_token::ClusterRegistrationToken;
 
+/// Label referencing the CAPI Cluster name on the objects created for it
+pub static CLUSTER_NAME_LABEL: &str = "cluster.x-k8s.io/cluster-name";
+
+/// Annotation pausing the CAPI Cluster reconciliation
+pub static PAUSED_ANNOTATION: &str = "cluster.x-k8s.io/paused";
+
+/// Label set by CAPI on control plane machines
+pub static CONTROL_PLANE_LABEL: &str = "cluster.x-k8s.io/control-plane";
+
+/// v1beta1 condition reporting the control plane readiness
+pub static CONTROLPLANE_READY_CONDITION: &str = "ControlPlaneReady";
+
+/// Condition under `status.v1beta2` reporting the control plane initialization
+pub static CONTROLPLANE_INITIALIZED_CONDITION: &str = "ControlPlaneInitialized";
+
+/// Machine condition reporting the node health
+pub static MACHINE_NODE_HEALTHY_CONDITION: &str = "NodeHealthy";
+
+/// Label recording the import profile used for the Fleet Cluster
+pub static IMPORT_PROFILE_LABEL: &str = "import-profile.fleet.addons.cluster.x-k8s.io";
+
+/// Prefix of the CAPI Cluster annotations overriding the Fleet agent configuration for the cluster
+pub static AGENT_OVERRIDE_PREFIX: &str = "agent.fleet.addons.cluster.x-k8s.io/";
+
+/// Annotation referencing a ConfigMap in the cluster namespace with the Fleet agent overrides
+pub static AGENT_CONFIG_MAP_ANNOTATION: &str = "agent.fleet.addons.cluster.x-k8s.io/config-map";
+
 #[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 #[resource(inherit = cluster_api_rs::capi_cluster::Cluster)]
 pub struct Cluster {
@@ -33,6 +70,279 @@ pub struct Cluster {
     pub status: Option<ClusterStatus>,
 }
 
+/// ClusterSpec holds the CAPI Cluster spec fields used by the addon provider. Clusters are read
+/// via the `v1beta1` API, so only the `v1beta1` contract format is parsed. Remaining fields are
+/// preserved for templating.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_plane_ref: Option<ObjectReference>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infrastructure_ref: Option<ObjectReference>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topology: Option<ClusterTopology>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+/// ClusterTopology references the ClusterClass with `class` and `classNamespace`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterTopology {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_namespace: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+/// ClusterStatus holds the CAPI Cluster readiness fields. The `v1beta1` API reports
+/// `controlPlaneReady` and `infrastructureReady`, with the new conditions under `v1beta2`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<Vec<Condition>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub control_plane_ready: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infrastructure_ready: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub v1beta2: Option<ClusterV1beta2Status>,
+
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ClusterV1beta2Status {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<Vec<Condition>>,
+}
+
+impl ClusterStatus {
+    /// Check if the condition is `True` in either the v1beta1 or v1beta2 condition list.
+    pub(crate) fn condition_true(&self, type_: &str) -> bool {
+        let v1beta2 = self.v1beta2.as_ref().and_then(|s| s.conditions.as_ref());
+        self.conditions
+            .iter()
+            .chain(v1beta2)
+            .flatten()
+            .any(|c| c.type_ == type_ && c.status == "True")
+    }
+
+    /// Check if the condition is present in either the v1beta1 or v1beta2 condition list.
+    pub(crate) fn has_condition(&self, type_: &str) -> bool {
+        let v1beta2 = self.v1beta2.as_ref().and_then(|s| s.conditions.as_ref());
+        self.conditions
+            .iter()
+            .chain(v1beta2)
+            .flatten()
+            .any(|c| c.type_ == type_)
+    }
+
+    /// Control plane readiness, reported by `controlPlaneReady`, the `ControlPlaneReady` condition
+    /// or the `ControlPlaneInitialized` condition under `v1beta2`.
+    pub(crate) fn control_plane_ready(&self) -> bool {
+        self.control_plane_ready.is_some_and(|ready| ready)
+            || self.condition_true(CONTROLPLANE_READY_CONDITION)
+            || self.condition_true(CONTROLPLANE_INITIALIZED_CONDITION)
+    }
+
+    pub(crate) fn infrastructure_ready(&self) -> bool {
+        self.infrastructure_ready.is_some_and(|ready| ready)
+    }
+}
+
+/// ConditionStatus is the status of a condition reported on the CAPI Cluster.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ConditionStatus {
+    True,
+    False,
+    #[default]
+    Unknown,
+}
+
+impl From<bool> for ConditionStatus {
+    fn from(status: bool) -> Self {
+        match status {
+            true => Self::True,
+            false => Self::False,
+        }
+    }
+}
+
+/// ClusterCondition is a condition reported by the addon provider on the CAPI Cluster.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClusterCondition {
+    pub type_: String,
+    pub status: ConditionStatus,
+    pub reason: String,
+    pub message: String,
+}
+
+impl ClusterCondition {
+    /// Render the condition in the v1beta1 `status.conditions` format
+    pub(crate) fn to_v1beta1(&self, last_transition_time: &str) -> Value {
+        let mut condition = json!({
+            "type": self.type_,
+            "status": self.status_string(),
+            "reason": self.reason,
+            "message": self.message,
+            "lastTransitionTime": last_transition_time,
+        });
+        if self.status == ConditionStatus::False {
+            condition["severity"] = "Warning".into();
+        }
+        condition
+    }
+
+    /// Render the condition in the v1beta2 `status.v1beta2.conditions` format
+    pub(crate) fn to_v1beta2(&self, last_transition_time: &str, generation: Option<i64>) -> Value {
+        json!({
+            "type": self.type_,
+            "status": self.status_string(),
+            "reason": self.reason,
+            "message": self.message,
+            "lastTransitionTime": last_transition_time,
+            "observedGeneration": generation,
+        })
+    }
+
+    fn status_string(&self) -> &'static str {
+        match self.status {
+            ConditionStatus::True => "True",
+            ConditionStatus::False => "False",
+            ConditionStatus::Unknown => "Unknown",
+        }
+    }
+
+    pub(crate) fn is_true(&self) -> bool {
+        self.status == ConditionStatus::True
+    }
+
+    /// Insert or replace the condition in the list, preserving the last transition time
+    /// when the status did not change. Returns true if the list was modified.
+    pub(crate) fn upsert(
+        &self,
+        conditions: &mut Vec<Value>,
+        render: impl Fn(&str) -> Value,
+    ) -> bool {
+        let now = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        let existing = conditions
+            .iter()
+            .position(|c| c["type"].as_str() == Some(&self.type_));
+
+        let transition_time = existing
+            .map(|i| &conditions[i])
+            .filter(|c| c["status"].as_str() == Some(self.status_string()))
+            .and_then(|c| c["lastTransitionTime"].as_str())
+            .unwrap_or(&now)
+            .to_string();
+
+        let condition = render(&transition_time);
+        match existing {
+            Some(i) if conditions[i] == condition => false,
+            Some(i) => {
+                conditions[i] = condition;
+                true
+            }
+            None => {
+                conditions.push(condition);
+                true
+            }
+        }
+    }
+}
+
+/// AgentOverrides are per-cluster Fleet agent settings, taking precedence over the `ClusterConfig`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AgentOverrides {
+    pub agent_namespace: Option<String>,
+    pub agent_tolerations: Option<Vec<ClusterAgentTolerations>>,
+    pub host_network: Option<bool>,
+    pub agent_env_vars: Option<Vec<ClusterAgentEnvVars>>,
+}
+
+impl AgentOverrides {
+    /// Parse overrides from key value pairs, collecting descriptions of invalid entries.
+    pub fn parse<'a>(entries: impl IntoIterator<Item = (&'a str, &'a str)>) -> (Self, Vec<String>) {
+        let mut overrides = Self::default();
+        let mut errors = vec![];
+        for (key, value) in entries {
+            let result = match key {
+                "namespace" if is_dns_label(value) => {
+                    overrides.agent_namespace = Some(value.into());
+                    Ok(())
+                }
+                "namespace" => {
+                    errors.push(format!(
+                        "agent override `{key}`: `{value}` is not a valid namespace name"
+                    ));
+                    continue;
+                }
+                "tolerations" => {
+                    serde_yaml::from_str(value).map(|v| overrides.agent_tolerations = Some(v))
+                }
+                "host-network" => {
+                    serde_yaml::from_str(value).map(|v| overrides.host_network = Some(v))
+                }
+                "env-vars" => {
+                    serde_yaml::from_str(value).map(|v| overrides.agent_env_vars = Some(v))
+                }
+                // ConfigMap reference is resolved by the controller
+                "config-map" => Ok(()),
+                _ => {
+                    errors.push(format!("unknown agent override `{key}`"));
+                    continue;
+                }
+            };
+
+            if let Err(e) = result {
+                errors.push(format!("agent override `{key}`: {e}"));
+            }
+        }
+
+        (overrides, errors)
+    }
+
+    /// Combine overrides, with values set in `other` taking precedence.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            agent_namespace: other.agent_namespace.or(self.agent_namespace),
+            agent_tolerations: other.agent_tolerations.or(self.agent_tolerations),
+            host_network: other.host_network.or(self.host_network),
+            agent_env_vars: other.agent_env_vars.or(self.agent_env_vars),
+        }
+    }
+}
+
+fn is_dns_label(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= 63
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !value.starts_with('-')
+        && !value.ends_with('-')
+}
+
 impl From<&Cluster> for ObjectMeta {
     fn from(cluster: &Cluster) -> Self {
         Self {
@@ -43,6 +353,49 @@ impl From<&Cluster> for ObjectMeta {
     }
 }
 
+/// Name of the ClusterGroup for the Kubernetes minor version.
+pub(crate) fn version_group_name(minor: &str) -> String {
+    format!("kubernetes-{minor}")
+}
+
+/// Minor version of the Kubernetes version, e.g. `v1.31` for `v1.31.2+rke2r1`.
+pub(crate) fn minor_version(version: &str) -> Option<String> {
+    let mut parts = version.trim_start_matches('v').split('.');
+    let major: u32 = parts.next()?.parse().ok()?;
+    let minor: u32 = parts.next()?.parse().ok()?;
+    Some(format!("v{major}.{minor}"))
+}
+
+/// Kubernetes version as a valid label value, replacing unsupported characters like `+`.
+fn version_label_value(version: &str) -> Option<String> {
+    let value: String = version
+        .chars()
+        .map(
+            |c| match c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                true => c,
+                false => '-',
+            },
+        )
+        .take(MAX_NAME_LENGTH)
+        .collect();
+    let value = value.trim_matches(|c: char| !c.is_ascii_alphanumeric());
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Name of the ClusterGroup for the provider kind, selected by the provider kind label.
+pub(crate) fn provider_group_name(label: &str, kind: &str) -> String {
+    let prefix = match label == INFRASTRUCTURE_KIND_LABEL {
+        true => "infrastructure",
+        false => "controlplane",
+    };
+    truncate_name(format!("{prefix}-{}", kind.to_lowercase()))
+}
+
+/// Name of the ClusterGroup for the ClusterClass, selected by the class and class namespace labels.
+pub(crate) fn class_group_name(class: &str, class_namespace: &str) -> String {
+    truncate_name(format!("{class}.{class_namespace}"))
+}
+
 impl Cluster {
     pub(crate) fn to_group(self: &Cluster, config: Option<&ClusterConfig>) -> Option<ClusterGroup> {
         config?.apply_class_group().then_some(true)?;
@@ -64,7 +417,7 @@ impl Cluster {
         Some(ClusterGroup {
             types: Some(TypeMeta::resource::<ClusterGroup>()),
             metadata: ObjectMeta {
-                name: Some(format!("{class}.{class_namespace}")),
+                name: Some(class_group_name(class, class_namespace)),
                 namespace: self.namespace(),
                 labels: labels.clone(),
                 owner_references: self.owner_ref(&()).into_iter().map(Into::into).collect(),
@@ -80,18 +433,112 @@ impl Cluster {
         })
     }
 
+    /// Labels with the infrastructure and control plane provider kinds, selected by the provider groups.
+    pub(crate) fn provider_labels(&self) -> BTreeMap<String, String> {
+        [
+            (INFRASTRUCTURE_KIND_LABEL, &self.spec.infrastructure_ref),
+            (CONTROL_PLANE_KIND_LABEL, &self.spec.control_plane_ref),
+        ]
+        .into_iter()
+        .filter_map(|(label, reference)| {
+            let kind = reference.as_ref()?.kind.clone()?;
+            Some((label.to_string(), kind))
+        })
+        .collect()
+    }
+
+    pub(crate) fn to_provider_groups(&self, config: Option<&ClusterConfig>) -> Vec<ClusterGroup> {
+        if !config.is_some_and(ClusterConfig::apply_provider_groups) {
+            return vec![];
+        }
+
+        self.provider_labels()
+            .into_iter()
+            .map(|(label, kind)| {
+                let name = provider_group_name(&label, &kind);
+                self.label_group(name, label, kind)
+            })
+            .collect()
+    }
+
+    /// Labels with the desired Kubernetes version from the cluster topology, and the observed version
+    /// reported by the control plane. The minor version prefers the observed version.
+    pub(crate) fn version_labels(&self, observed: Option<&str>) -> BTreeMap<String, String> {
+        let desired = self
+            .spec
+            .topology
+            .as_ref()
+            .and_then(|t| t.version.as_deref());
+        let mut labels = BTreeMap::new();
+        if let Some(version) = desired.and_then(version_label_value) {
+            labels.insert(KUBERNETES_VERSION_LABEL.to_string(), version);
+        }
+        if let Some(version) = observed.and_then(version_label_value) {
+            labels.insert(KUBERNETES_OBSERVED_VERSION_LABEL.to_string(), version);
+        }
+        if let Some(minor) = observed.or(desired).and_then(minor_version) {
+            labels.insert(KUBERNETES_MINOR_VERSION_LABEL.to_string(), minor);
+        }
+
+        labels
+    }
+
+    pub(crate) fn to_version_group(
+        &self,
+        config: Option<&ClusterConfig>,
+        observed: Option<&str>,
+    ) -> Option<ClusterGroup> {
+        config?.apply_version_groups().then_some(true)?;
+
+        let minor = self
+            .version_labels(observed)
+            .remove(KUBERNETES_MINOR_VERSION_LABEL)?;
+        Some(self.label_group(
+            version_group_name(&minor),
+            KUBERNETES_MINOR_VERSION_LABEL.to_string(),
+            minor,
+        ))
+    }
+
+    /// ClusterGroup in the cluster namespace, selecting clusters with the label value. The group is
+    /// shared by the clusters in the namespace, and is removed once no Fleet cluster has the label.
+    fn label_group(&self, name: String, label: String, value: String) -> ClusterGroup {
+        let labels = Some(BTreeMap::from([(label, value)]));
+        ClusterGroup {
+            types: Some(TypeMeta::resource::<ClusterGroup>()),
+            metadata: ObjectMeta {
+                name: Some(name),
+                namespace: self.namespace(),
+                labels: labels.clone(),
+                ..Default::default()
+            },
+            spec: ClusterGroupSpec {
+                selector: Some(ClusterGroupSelector {
+                    match_labels: labels,
+                    ..Default::default()
+                }),
+            },
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn to_cluster(
         self: &Cluster,
         config: Option<&ClusterConfig>,
-    ) -> fleet_cluster::Cluster {
+    ) -> Result<fleet_cluster::Cluster, NamingError> {
         let empty = ClusterConfig::default();
         let config = config.unwrap_or(&empty);
         let class = self.cluster_class_name();
         let ns = self.namespace().unwrap_or_default();
         let class_namespace = self.cluster_class_namespace().unwrap_or(&ns);
-        let annotations = self.annotations().clone();
+        let annotations = config.sync_annotations(self.annotations());
         let labels = {
-            let mut labels = self.labels().clone();
+            let mut labels = config.sync_labels(self.labels());
+            labels.insert(CLUSTER_NAME_LABEL.to_string(), self.name_any());
+            if config.apply_provider_groups() {
+                labels.extend(self.provider_labels());
+            }
+            labels.extend(self.version_labels(None));
             if let Some(class) = class {
                 labels.insert(CLUSTER_CLASS_LABEL.to_string(), class.to_string());
                 labels.insert(
@@ -102,7 +549,7 @@ impl Cluster {
             labels
         };
 
-        fleet_cluster::Cluster {
+        Ok(fleet_cluster::Cluster {
             types: Some(TypeMeta::resource::<fleet_cluster::Cluster>()),
             metadata: ObjectMeta {
                 annotations: Some(annotations),
@@ -111,41 +558,48 @@ impl Cluster {
                     .set_owner_references
                     .is_some_and(|set| set)
                     .then_some(self.owner_ref(&()).into_iter().collect()),
-                name: config.apply_naming(self.name_any()).into(),
+                name: config.apply_naming(&self.naming_variables())?.into(),
                 ..self.into()
             },
             #[cfg(feature = "agent-initiated")]
             spec: match config.agent_initiated_connection() {
                 true => fleet_api_rs::fleet_cluster::ClusterSpec {
                     client_id: Some(Alphanumeric.sample_string(&mut rand::rng(), 64)),
-                    agent_namespace: config.agent_install_namespace().into(),
-                    agent_tolerations: config.agent_tolerations().into(),
-                    host_network: config.host_network,
-                    agent_env_vars: config.agent_env_vars.clone(),
-                    ..Default::default()
+                    ..config.agent_spec()
                 }
                 .into(),
                 false => fleet_api_rs::fleet_cluster::ClusterSpec {
-                    kube_config_secret: Some(format!("{}-kubeconfig", self.name_any())),
-                    agent_namespace: config.agent_install_namespace().into(),
-                    agent_tolerations: config.agent_tolerations().into(),
-                    host_network: config.host_network,
-                    agent_env_vars: config.agent_env_vars.clone(),
-                    ..Default::default()
+                    kube_config_secret: Some(self.fleet_kubeconfig_secret_name(config)),
+                    ..config.agent_spec()
                 }
                 .into(),
             },
             #[cfg(not(feature = "agent-initiated"))]
             spec: fleet_api_rs::fleet_cluster::ClusterSpec {
-                kube_config_secret: Some(format!("{}-kubeconfig", self.name_any())),
-                agent_namespace: config.agent_install_namespace().into(),
-                agent_tolerations: config.agent_tolerations().into(),
-                host_network: config.host_network,
-                agent_env_vars: config.agent_env_vars.clone(),
-                ..Default::default()
+                kube_config_secret: Some(self.fleet_kubeconfig_secret_name(config)),
+                ..config.agent_spec()
             },
             ..Default::default()
+        })
+    }
+
+    /// Variables available in the naming strategy template.
+    pub(crate) fn naming_variables(&self) -> BTreeMap<&'static str, String> {
+        let mut variables = BTreeMap::from([
+            ("name", self.name_any()),
+            ("namespace", self.namespace().unwrap_or_default()),
+        ]);
+        if let Some(class) = self.cluster_class_name() {
+            let class_namespace = self
+                .cluster_class_namespace()
+                .map(ToString::to_string)
+                .or(self.namespace())
+                .unwrap_or_default();
+            variables.insert("class", class.to_string());
+            variables.insert("classNamespace", class_namespace);
         }
+
+        variables
     }
 
     pub(crate) fn to_bundle_ns_mapping(
@@ -154,8 +608,7 @@ impl Cluster {
     ) -> Option<BundleNamespaceMapping> {
         config?.apply_class_group().then_some(true)?;
 
-        let topology = self.spec.topology.as_ref()?;
-        let class_namespace = topology.class_namespace.clone()?;
+        let class_namespace = self.cluster_class_namespace()?.to_string();
 
         let match_labels = {
             let mut labels = BTreeMap::default();
@@ -198,11 +651,424 @@ impl Cluster {
         .into()
     }
 
+    /// Agent overrides set in the cluster annotations, along with invalid annotation descriptions.
+    pub(crate) fn annotation_overrides(&self) -> (AgentOverrides, Vec<String>) {
+        AgentOverrides::parse(self.annotations().iter().filter_map(|(key, value)| {
+            Some((key.strip_prefix(AGENT_OVERRIDE_PREFIX)?, value.as_str()))
+        }))
+    }
+
+    pub(crate) fn agent_config_map(&self) -> Option<&String> {
+        self.annotations().get(AGENT_CONFIG_MAP_ANNOTATION)
+    }
+
     pub(crate) fn cluster_class_namespace(&self) -> Option<&str> {
         self.spec.topology.as_ref()?.class_namespace.as_deref()
     }
 
     pub(crate) fn cluster_class_name(&self) -> Option<&str> {
-        Some(&self.spec.topology.as_ref()?.class)
+        self.spec.topology.as_ref()?.class.as_deref()
+    }
+
+    /// Readiness gate requirements reported by the cluster status, which are not met yet.
+    pub(crate) fn pending_status_requirements(&self, gate: &ReadinessGate) -> Vec<String> {
+        let status = self.status.clone().unwrap_or_default();
+        let mut pending: Vec<String> = gate
+            .conditions
+            .iter()
+            .flatten()
+            .filter(|type_| !status.condition_true(type_))
+            .map(|type_| format!("condition {type_}"))
+            .collect();
+
+        if gate.infrastructure_ready.is_some_and(|required| required)
+            && !status.infrastructure_ready()
+        {
+            pending.push("infrastructure readiness".into());
+        }
+
+        pending
+    }
+
+    /// Check if the Fleet resource is managed for this cluster, either by the cluster name label,
+    /// or by the owner reference.
+    pub(crate) fn owns(&self, res: &impl Resource) -> bool {
+        let labelled = res.labels().get(CLUSTER_NAME_LABEL) == Some(&self.name_any());
+        let referenced = self.uid().is_some_and(|uid| {
+            res.owner_references()
+                .iter()
+                .any(|owner| owner.uid == uid && owner.kind == Cluster::kind(&()))
+        });
+        labelled || referenced
+    }
+
+    /// Check if the Fleet cluster is managed for this cluster, including Fleet clusters imported
+    /// before the cluster name label was introduced.
+    pub(crate) fn owns_fleet_cluster(&self, fleet: &fleet_cluster::Cluster) -> bool {
+        self.owns(fleet) || fleet.capi_cluster_name() == Some(self.name_any())
+    }
+
+    /// Check if the cluster is paused with `spec.paused`, or the paused annotation.
+    pub(crate) fn paused(&self) -> bool {
+        self.spec.paused.is_some_and(|paused| paused)
+            || self.annotations().contains_key(PAUSED_ANNOTATION)
+    }
+
+    pub(crate) fn kubeconfig_secret_name(&self) -> String {
+        format!("{}-kubeconfig", self.name_any())
+    }
+
+    /// Kubeconfig secret used by Fleet, either the CAPI admin kubeconfig, or the
+    /// ServiceAccount kubeconfig minted by the addon provider.
+    pub(crate) fn fleet_kubeconfig_secret_name(&self, config: &ClusterConfig) -> String {
+        match config.service_account_kubeconfig {
+            Some(_) => format!("{}-fleet-kubeconfig", self.name_any()),
+            None => self.kubeconfig_secret_name(),
+        }
+    }
+}
+
+/// Check if the worker machine has a healthy node.
+pub(crate) fn machine_ready(machine: &Machine) -> bool {
+    let Some(status) = &machine.status else {
+        return false;
+    };
+
+    let v1beta2 = status.v1beta2.as_ref().and_then(|s| s.conditions.as_ref());
+    status.node_ref.is_some()
+        && status
+            .conditions
+            .iter()
+            .chain(v1beta2)
+            .flatten()
+            .any(|c| c.type_ == MACHINE_NODE_HEALTHY_CONDITION && c.status == "True")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use kube::api::ObjectMeta;
+
+    use serde_json::json;
+
+    use crate::api::{
+        fleet_addon_config::{ClusterConfig, ReadinessGate},
+        fleet_cluster,
+    };
+
+    use super::{minor_version, AgentOverrides, Cluster};
+
+    #[test]
+    fn test_cluster_contracts() {
+        let v1beta1: Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default"},
+            "spec": {
+                "clusterNetwork": {"pods": {"cidrBlocks": ["10.0.0.0/16"]}},
+                "topology": {"class": "quick-start", "classNamespace": "classes", "version": "v1.31.0"},
+                "controlPlaneRef": {"apiVersion": "controlplane.cluster.x-k8s.io/v1beta1", "kind": "KubeadmControlPlane", "name": "cp"},
+            },
+            "status": {
+                "controlPlaneReady": false,
+                "v1beta2": {"conditions": [
+                    {"type": "ControlPlaneInitialized", "status": "True", "lastTransitionTime": "2025-01-01T00:00:00Z"},
+                ]},
+            },
+        }))
+        .unwrap();
+        assert_eq!(Some("quick-start"), v1beta1.cluster_class_name());
+        assert_eq!(Some("classes"), v1beta1.cluster_class_namespace());
+        assert!(v1beta1.status.as_ref().unwrap().control_plane_ready());
+        assert!(v1beta1.spec.other.contains_key("clusterNetwork"));
+
+        assert!(!v1beta1.status.as_ref().unwrap().infrastructure_ready());
+        assert_eq!(
+            Some("controlplane.cluster.x-k8s.io/v1beta1"),
+            v1beta1
+                .spec
+                .control_plane_ref
+                .unwrap()
+                .api_version
+                .as_deref()
+        );
+    }
+
+    #[test]
+    fn test_agent_spec() {
+        let config: ClusterConfig = serde_json::from_value(json!({
+            "namespaceSelector": {},
+            "selector": {},
+            "agentResources": {"limits": {"memory": "128Mi"}},
+            "agentAffinity": {"nodeAffinity": {"requiredDuringSchedulingIgnoredDuringExecution": {
+                "nodeSelectorTerms": [{"matchExpressions": [{"key": "edge", "operator": "Exists"}]}],
+            }}},
+            "privateRepoURL": "registry.example.com",
+            "redeployAgentGeneration": 2,
+        }))
+        .unwrap();
+
+        let cluster: Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default"},
+            "spec": {},
+        }))
+        .unwrap();
+        let spec = cluster.to_cluster(Some(&config)).unwrap().spec;
+        assert_eq!(spec.agent_resources, config.agent_resources);
+        assert!(spec.agent_affinity.is_some());
+        assert_eq!(
+            spec.private_repo_url.as_deref(),
+            Some("registry.example.com")
+        );
+        assert_eq!(spec.redeploy_agent_generation, Some(2));
+        assert_eq!(
+            spec.kube_config_secret.as_deref(),
+            Some("cluster-kubeconfig")
+        );
+    }
+
+    #[test]
+    fn test_provider_groups() {
+        let cluster: Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default", "uid": "uid"},
+            "spec": {
+                "infrastructureRef": {"apiVersion": "infrastructure.cluster.x-k8s.io/v1beta2", "kind": "AWSCluster", "name": "cluster"},
+                "controlPlaneRef": {"apiVersion": "controlplane.cluster.x-k8s.io/v1beta1", "kind": "RKE2ControlPlane", "name": "cluster"},
+            },
+        }))
+        .unwrap();
+
+        let config = ClusterConfig::default();
+        assert!(cluster.to_provider_groups(Some(&config)).is_empty());
+
+        let config = ClusterConfig {
+            apply_provider_groups: Some(true),
+            ..Default::default()
+        };
+        // Groups are shared by the clusters with the same provider, and not owned by any of them
+        assert!(cluster
+            .to_provider_groups(Some(&config))
+            .iter()
+            .all(|g| g.metadata.owner_references.is_none()));
+
+        let groups: Vec<_> = cluster
+            .to_provider_groups(Some(&config))
+            .into_iter()
+            .map(|g| {
+                (
+                    g.metadata.name.unwrap(),
+                    g.spec.selector.unwrap().match_labels,
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "controlplane-rke2controlplane".to_string(),
+                    Some(BTreeMap::from([(
+                        "controlplane-kind.fleet.addons.cluster.x-k8s.io".to_string(),
+                        "RKE2ControlPlane".to_string()
+                    )]))
+                ),
+                (
+                    "infrastructure-awscluster".to_string(),
+                    Some(BTreeMap::from([(
+                        "infrastructure-kind.fleet.addons.cluster.x-k8s.io".to_string(),
+                        "AWSCluster".to_string()
+                    )]))
+                ),
+            ]
+        );
+
+        let labels = cluster
+            .to_cluster(Some(&config))
+            .unwrap()
+            .metadata
+            .labels
+            .unwrap();
+        assert_eq!(
+            labels.get("infrastructure-kind.fleet.addons.cluster.x-k8s.io"),
+            Some(&"AWSCluster".to_string())
+        );
+    }
+
+    #[test]
+    fn test_version_labels() {
+        assert_eq!(minor_version("v1.31.2+rke2r1").as_deref(), Some("v1.31"));
+        assert_eq!(minor_version("1.30.0").as_deref(), Some("v1.30"));
+        assert_eq!(minor_version("latest"), None);
+
+        let cluster: Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default"},
+            "spec": {"topology": {"class": "quick-start", "version": "v1.31.2+rke2r1"}},
+        }))
+        .unwrap();
+
+        assert_eq!(
+            cluster.version_labels(None),
+            BTreeMap::from([
+                (
+                    "kubernetes-version.fleet.addons.cluster.x-k8s.io".to_string(),
+                    "v1.31.2-rke2r1".to_string()
+                ),
+                (
+                    "kubernetes-minor-version.fleet.addons.cluster.x-k8s.io".to_string(),
+                    "v1.31".to_string()
+                ),
+            ])
+        );
+
+        // Observed version takes precedence for the minor version during upgrades
+        let labels = cluster.version_labels(Some("v1.30.5+rke2r1"));
+        assert_eq!(
+            labels.get("kubernetes-observed-version.fleet.addons.cluster.x-k8s.io"),
+            Some(&"v1.30.5-rke2r1".to_string())
+        );
+        assert_eq!(
+            labels.get("kubernetes-minor-version.fleet.addons.cluster.x-k8s.io"),
+            Some(&"v1.30".to_string())
+        );
+
+        let config = ClusterConfig {
+            apply_version_groups: Some(true),
+            ..Default::default()
+        };
+        let group = cluster.to_version_group(Some(&config), None).unwrap();
+        assert_eq!(group.metadata.name.as_deref(), Some("kubernetes-v1.31"));
+        assert!(cluster
+            .to_version_group(Some(&ClusterConfig::default()), None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_owns() {
+        let cluster: Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default", "uid": "1234"},
+            "spec": {},
+        }))
+        .unwrap();
+
+        let unmanaged: fleet_cluster::Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default"},
+            "spec": {},
+        }))
+        .unwrap();
+        assert!(!cluster.owns(&unmanaged));
+
+        let labelled: fleet_cluster::Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "labels": {"cluster.x-k8s.io/cluster-name": "cluster"}},
+            "spec": {},
+        }))
+        .unwrap();
+        assert!(cluster.owns(&labelled));
+
+        let referenced: fleet_cluster::Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "ownerReferences": [{
+                "apiVersion": "cluster.x-k8s.io/v1beta1",
+                "kind": "Cluster",
+                "name": "cluster",
+                "uid": "1234",
+            }]},
+            "spec": {},
+        }))
+        .unwrap();
+        assert!(cluster.owns(&referenced));
+    }
+
+    #[test]
+    fn test_paused() {
+        let mut cluster = Cluster::default();
+        assert!(!cluster.paused());
+
+        cluster.spec.paused = Some(true);
+        assert!(cluster.paused());
+
+        cluster.spec.paused = Some(false);
+        cluster.metadata.annotations = Some(BTreeMap::from([(
+            "cluster.x-k8s.io/paused".to_string(),
+            "".to_string(),
+        )]));
+        assert!(cluster.paused());
+    }
+
+    #[test]
+    fn test_pending_status_requirements() {
+        let gate = ReadinessGate {
+            conditions: Some(vec!["InfrastructureReady".into(), "Custom".into()]),
+            infrastructure_ready: Some(true),
+            ..Default::default()
+        };
+
+        let mut cluster = Cluster::default();
+        assert_eq!(
+            vec![
+                "condition InfrastructureReady",
+                "condition Custom",
+                "infrastructure readiness"
+            ],
+            cluster.pending_status_requirements(&gate)
+        );
+
+        cluster.status = serde_json::from_value(json!({
+            "infrastructureReady": true,
+            "conditions": [
+                {"type": "InfrastructureReady", "status": "True", "lastTransitionTime": "2025-01-01T00:00:00Z"},
+                {"type": "Custom", "status": "False", "lastTransitionTime": "2025-01-01T00:00:00Z"},
+            ],
+        }))
+        .unwrap();
+        assert_eq!(
+            vec!["condition Custom"],
+            cluster.pending_status_requirements(&gate)
+        );
+    }
+
+    #[test]
+    fn test_annotation_overrides() {
+        let cluster = Cluster {
+            metadata: ObjectMeta {
+                annotations: Some(BTreeMap::from([
+                    (
+                        "agent.fleet.addons.cluster.x-k8s.io/host-network".into(),
+                        "false".into(),
+                    ),
+                    (
+                        "agent.fleet.addons.cluster.x-k8s.io/tolerations".into(),
+                        "[{key: gpu, operator: Exists, effect: NoSchedule}]".into(),
+                    ),
+                    (
+                        "agent.fleet.addons.cluster.x-k8s.io/namespace".into(),
+                        "Invalid_NS".into(),
+                    ),
+                    (
+                        "agent.fleet.addons.cluster.x-k8s.io/unknown".into(),
+                        "value".into(),
+                    ),
+                    ("other.io/annotation".into(), "ignored".into()),
+                ])),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (overrides, errors) = cluster.annotation_overrides();
+        assert_eq!(overrides.host_network, Some(false));
+        assert_eq!(overrides.agent_namespace, None);
+        let tolerations = overrides.agent_tolerations.unwrap();
+        assert_eq!(tolerations.len(), 1);
+        assert_eq!(tolerations[0].key.as_deref(), Some("gpu"));
+        assert_eq!(errors.len(), 2);
+
+        let merged = AgentOverrides {
+            agent_namespace: Some("agents".into()),
+            host_network: Some(true),
+            ..Default::default()
+        }
+        .merge(AgentOverrides {
+            host_network: Some(false),
+            ..Default::default()
+        });
+        assert_eq!(merged.agent_namespace.as_deref(), Some("agents"));
+        assert_eq!(merged.host_network, Some(false));
     }
 }
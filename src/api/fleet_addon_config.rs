@@ -1,23 +1,43 @@
-use std::{fmt::Display, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
 
-use fleet_api_rs::fleet_cluster::{ClusterAgentEnvVars, ClusterAgentTolerations};
+use chrono::{DateTime, Utc};
+use fleet_api_rs::fleet_cluster::{
+    ClusterAgentAffinity, ClusterAgentEnvVars, ClusterAgentResources, ClusterAgentTolerations,
+    ClusterSpec,
+};
 use k8s_openapi::{
     api::core::v1::{ConfigMap, ObjectReference},
     apimachinery::pkg::apis::meta::v1::{Condition, LabelSelector},
 };
 use kube::{
     api::{ObjectMeta, TypeMeta},
-    core::{ParseExpressionError, Selector},
+    core::{ParseExpressionError, Selector, SelectorExt as _},
     CELSchema, CustomResource, Resource,
 };
 use schemars::JsonSchema;
 use serde::{ser, Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use serde_yaml::Value;
+use sha2::{Digest as _, Sha256};
+use thiserror::Error;
+
+use super::capi_cluster::AgentOverrides;
 
 pub const AGENT_NAMESPACE: &str = "fleet-addon-agent";
+pub const DEFAULT_PROFILE: &str = "default";
+pub const FLEET_NAMESPACE: &str = "cattle-fleet-system";
+pub const FLEET_CONFIG_NAME: &str = "fleet-controller";
+pub const SERVICE_ACCOUNT_NAMESPACE: &str = "kube-system";
+pub const SERVICE_ACCOUNT_TOKEN_EXPIRATION: i64 = 24 * 60 * 60;
+pub const DRAIN_TIMEOUT: i64 = 10 * 60;
+pub const ROLLOUT_TIMEOUT: i64 = 60;
+pub const AGENT_DISCONNECT_TIMEOUT: i64 = 3 * 15 * 60;
+pub const FLEET_REPOSITORY_NAME: &str = "fleet";
+pub const FLEET_REPOSITORY_URL: &str = "https://rancher.github.io/fleet-helm-charts/";
 pub const EXPERIMENTAL_OCI_STORAGE: &str = "EXPERIMENTAL_OCI_STORAGE";
 pub const EXPERIMENTAL_HELM_OPS: &str = "EXPERIMENTAL_HELM_OPS";
+/// Label marking ConfigMaps and Secrets with chart values, which are watched for changes
+pub const CHART_VALUES_LABEL: &str = "chart-values.fleet.addons.cluster.x-k8s.io";
 
 /// This provides a config for fleet addon functionality
 #[derive(CustomResource, Deserialize, Serialize, Clone, Default, Debug, CELSchema)]
@@ -42,11 +62,50 @@ pub struct FleetAddonConfigSpec {
     /// will be added to the Fleet Cluster labels.
     pub cluster: Option<ClusterConfig>,
 
+    /// Named import profiles, evaluated in order. A cluster is imported with the settings of the first
+    /// profile matching its selectors, falling back to the `cluster` settings if none of the profiles match.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profiles: Option<Vec<ImportProfile>>,
+
     // Fleet chart configuratoin options
     pub config: Option<FleetConfig>,
 
     // Fleet chart installation options
     pub install: Option<FleetInstall>,
+
+    /// Namespace where Fleet is installed. Defaults to `cattle-fleet-system`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fleet_namespace: Option<String>,
+
+    /// Name of the Fleet controller ConfigMap in the Fleet namespace. Defaults to `fleet-controller`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fleet_config_name: Option<String>,
+
+    /// Preview changes to Fleet Clusters, ClusterGroups and BundleNamespaceMappings without applying them.
+    /// Planned creates, patches and deletes are reported as events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dry_run: Option<bool>,
+
+    /// Policy for the Fleet resources of previously imported clusters, which no longer match any of the
+    /// import selectors, or when cluster import is disabled. Defaults to `Orphan`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deimport_policy: Option<DeimportPolicy>,
+
+    /// Time in seconds since the last Fleet agent check-in, after which the agent is reported
+    /// as disconnected on the CAPI Cluster. Defaults to 45 minutes, three times the Fleet check-in interval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 0))]
+    pub agent_disconnect_timeout_seconds: Option<i64>,
+}
+
+/// DeimportPolicy controls the Fleet resources of clusters leaving the import scope.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, JsonSchema, Default, PartialEq)]
+pub enum DeimportPolicy {
+    /// Delete the Fleet Cluster, along with the ClusterGroup and BundleNamespaceMapping no longer used by other clusters.
+    Delete,
+    /// Keep the Fleet resources in place.
+    #[default]
+    Orphan,
 }
 
 impl Default for FleetAddonConfig {
@@ -68,6 +127,12 @@ impl Default for FleetAddonConfig {
 #[serde(rename_all = "camelCase")]
 pub struct FleetAddonConfigStatus {
     pub installed_version: Option<String>,
+    /// Hash of the user supplied chart values, applied with the latest install or upgrade.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values_hash: Option<String>,
+    /// Revision of the fleet release preceding the latest upgrade, used as a rollback target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_revision: Option<String>,
     /// conditions represents the observations of a Fleet addon current state.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub conditions: Vec<Condition>,
@@ -102,6 +167,15 @@ pub struct ClusterConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub apply_class_group: Option<bool>,
 
+    /// Apply ClusterGroups for the infrastructure and control plane provider kinds of the cluster,
+    /// such as `AWSCluster` or `RKE2ControlPlane`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apply_provider_groups: Option<bool>,
+
+    /// Apply a ClusterGroup for each Kubernetes minor version of the imported clusters, such as `v1.31`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub apply_version_groups: Option<bool>,
+
     /// Allow to patch resources, maintaining the desired state.
     /// If is not set, resources will only be re-created in case of removal.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -132,17 +206,87 @@ pub struct ClusterConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub agent_env_vars: Option<Vec<ClusterAgentEnvVars>>,
 
+    /// AgentAffinity overrides the default affinity for the agent deployment.
+    /// If not set, the Fleet default affinity is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_affinity: Option<ClusterAgentAffinity>,
+
+    /// AgentResources sets the resource requests and limits for the agent deployment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_resources: Option<ClusterAgentResources>,
+
+    /// PrivateRepoURL prefixes the agent image name, overriding the global repository URL from the agent config.
+    #[serde(
+        default,
+        rename = "privateRepoURL",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub private_repo_url: Option<String>,
+
+    /// RedeployAgentGeneration forces the agent redeployment when changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redeploy_agent_generation: Option<i64>,
+
     /// Import settings for the CAPI cluster. Allows to import clusters based on a set of labels,
     /// set on the cluster or the namespace.
     #[serde(flatten)]
     pub selectors: Selectors,
 
+    /// Readiness requirements for the CAPI cluster, checked in addition to the control plane
+    /// readiness before the cluster is imported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readiness: Option<ReadinessGate>,
+
+    /// Rules for the labels copied from the CAPI Cluster to the Fleet Cluster. The `spec.cluster` rules
+    /// also apply to the ClusterGroup labels inherited from the ClusterClass.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_sync: Option<SyncRules>,
+
+    /// Rules for the annotations copied from the CAPI Cluster to the Fleet Cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation_sync: Option<SyncRules>,
+
+    /// Pause the Fleet Cluster while the CAPI Cluster is paused, stopping BundleDeployment rollouts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pause_fleet_cluster: Option<bool>,
+
+    /// Provide Fleet with a dedicated ServiceAccount kubeconfig for the workload cluster,
+    /// instead of the CAPI admin kubeconfig. This is not a least-privilege identity: the ServiceAccount
+    /// is granted `bind` and `escalate` on roles, as Fleet creates the agent RBAC, so it is effectively
+    /// cluster-admin in the workload cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_account_kubeconfig: Option<ServiceAccountKubeconfig>,
+
+    /// Drain Fleet workloads from the cluster on CAPI Cluster deletion, before the cluster is released
+    /// for infrastructure teardown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drain: Option<Drain>,
+
+    /// Policy for a pre-existing Fleet Cluster with the same name, not created by the addon provider
+    /// for this CAPI Cluster. Defaults to `Refuse`. With `Adopt`, the existing Fleet Cluster is
+    /// overwritten and deleted along with the CAPI Cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adoption_policy: Option<AdoptionPolicy>,
+
     #[cfg(feature = "agent-initiated")]
     /// Prepare initial cluster for agent initiated connection
     #[serde(skip_serializing_if = "Option::is_none")]
     pub agent_initiated: Option<bool>,
 }
 
+/// ImportProfile is a named set of cluster import settings, applied to clusters matching the profile selectors.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProfile {
+    /// Name of the profile, recorded in the imported Fleet Cluster labels.
+    #[schemars(length(max = 63), regex(pattern = r"^[a-z0-9]([-a-z0-9]*[a-z0-9])?$"))]
+    pub name: String,
+
+    /// Import settings for the clusters matching the profile.
+    #[serde(flatten)]
+    pub config: ClusterConfig,
+}
+
 #[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
 #[resource(inherit = ConfigMap)]
 pub struct FleetSettings {
@@ -178,10 +322,49 @@ impl Display for FleetChartValues {
 }
 
 impl FleetAddonConfigSpec {
+    /// Returns namespace where Fleet is installed.
+    pub(crate) fn fleet_namespace(&self) -> String {
+        self.fleet_namespace
+            .clone()
+            .unwrap_or(FLEET_NAMESPACE.to_string())
+    }
+
+    /// Returns name of the Fleet controller ConfigMap.
+    pub(crate) fn fleet_config_name(&self) -> String {
+        self.fleet_config_name
+            .clone()
+            .unwrap_or(FLEET_CONFIG_NAME.to_string())
+    }
+
     /// Returns reference to FeatureGates if defined.
     pub(crate) fn feature_gates(&self) -> Option<&FeatureGates> {
         self.config.as_ref()?.feature_gates.as_ref()
     }
+
+    /// Returns true if chart values reference the resource.
+    pub(crate) fn values_reference(&self, kind: &str, namespace: &str, name: &str) -> bool {
+        let Some(install) = self.install.as_ref() else {
+            return false;
+        };
+
+        [Some(&install.values), install.crd.as_ref()]
+            .into_iter()
+            .flatten()
+            .flat_map(|values| values.values_from.iter().flatten())
+            .any(|values| {
+                values.reference.kind.as_deref().unwrap_or("ConfigMap") == kind
+                    && values.reference.namespace.as_deref() == Some(namespace)
+                    && values.reference.name.as_deref() == Some(name)
+            })
+    }
+
+    /// Returns chart repository to install Fleet from, or the default public repository.
+    pub(crate) fn chart_repository(&self) -> ChartRepository {
+        self.install
+            .as_ref()
+            .and_then(|install| install.repository.clone())
+            .unwrap_or_default()
+    }
 }
 
 impl ClusterConfig {
@@ -191,6 +374,21 @@ impl ClusterConfig {
             .unwrap_or(AGENT_NAMESPACE.to_string())
     }
 
+    /// Fleet cluster spec with the agent deployment settings.
+    pub(crate) fn agent_spec(&self) -> ClusterSpec {
+        ClusterSpec {
+            agent_namespace: self.agent_install_namespace().into(),
+            agent_tolerations: self.agent_tolerations().into(),
+            agent_affinity: self.agent_affinity.clone(),
+            agent_resources: self.agent_resources.clone(),
+            host_network: self.host_network,
+            agent_env_vars: self.agent_env_vars.clone(),
+            private_repo_url: self.private_repo_url.clone(),
+            redeploy_agent_generation: self.redeploy_agent_generation,
+            ..Default::default()
+        }
+    }
+
     pub(crate) fn agent_tolerations(&self) -> Vec<ClusterAgentTolerations> {
         let agent_tolerations = vec![
             ClusterAgentTolerations {
@@ -222,14 +420,252 @@ impl ClusterConfig {
         self.agent_initiated.filter(|&set| set).is_some()
     }
 
-    pub(crate) fn apply_naming(&self, name: String) -> String {
-        let strategy = self.naming.clone().unwrap_or_default();
-        strategy.apply(name.clone().into()).unwrap_or(name)
+    pub(crate) fn apply_naming(
+        &self,
+        variables: &BTreeMap<&str, String>,
+    ) -> Result<String, NamingError> {
+        self.naming.clone().unwrap_or_default().render(variables)
+    }
+
+    /// Labels to copy from the CAPI Cluster, filtered and renamed according to the label sync rules.
+    pub(crate) fn sync_labels(
+        &self,
+        labels: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, String> {
+        self.label_sync.clone().unwrap_or_default().apply(labels)
+    }
+
+    /// Annotations to copy from the CAPI Cluster, filtered and renamed according to the annotation sync rules.
+    pub(crate) fn sync_annotations(
+        &self,
+        annotations: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, String> {
+        self.annotation_sync
+            .clone()
+            .unwrap_or_default()
+            .apply(annotations)
+    }
+
+    pub(crate) fn adoption_policy(&self) -> AdoptionPolicy {
+        self.adoption_policy.unwrap_or_default()
+    }
+
+    pub(crate) fn pause_fleet_cluster(&self) -> bool {
+        self.pause_fleet_cluster.is_some_and(|enabled| enabled)
     }
 
     pub(crate) fn apply_class_group(&self) -> bool {
         self.apply_class_group.is_some_and(|enabled| enabled)
     }
+
+    pub(crate) fn apply_provider_groups(&self) -> bool {
+        self.apply_provider_groups.is_some_and(|enabled| enabled)
+    }
+
+    pub(crate) fn apply_version_groups(&self) -> bool {
+        self.apply_version_groups.is_some_and(|enabled| enabled)
+    }
+
+    // Check for cluster patching setting.
+    pub(crate) fn patch_enabled(&self) -> bool {
+        self.patch_resource.is_some_and(|enabled| enabled)
+    }
+
+    /// Config with per-cluster agent overrides applied. Override tolerations are added to the
+    /// configured ones, and override env vars replace configured variables with the same name.
+    pub(crate) fn with_overrides(&self, overrides: AgentOverrides) -> Self {
+        let mut config = self.clone();
+        if let Some(namespace) = overrides.agent_namespace {
+            config.agent_namespace = Some(namespace);
+        }
+
+        if let Some(tolerations) = overrides.agent_tolerations {
+            let mut agent_tolerations = self.agent_tolerations();
+            agent_tolerations.extend(tolerations);
+            config.agent_tolerations = Some(agent_tolerations);
+        }
+
+        if let Some(host_network) = overrides.host_network {
+            config.host_network = Some(host_network);
+        }
+
+        if let Some(env_vars) = overrides.agent_env_vars {
+            let mut agent_env_vars = self.agent_env_vars.clone().unwrap_or_default();
+            agent_env_vars.retain(|var| env_vars.iter().all(|o| o.name != var.name));
+            agent_env_vars.extend(env_vars);
+            config.agent_env_vars = Some(agent_env_vars);
+        }
+
+        config
+    }
+}
+
+/// ReadinessGate lists the CAPI cluster requirements to be met before the cluster is imported.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessGate {
+    /// Condition types required to be `True` on the CAPI cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<Vec<String>>,
+
+    /// Require the cluster infrastructure to be ready.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub infrastructure_ready: Option<bool>,
+
+    /// Require the `<cluster>-kubeconfig` secret to exist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kubeconfig_secret: Option<bool>,
+
+    /// Minimum number of worker machines with a healthy node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_ready_workers: Option<u32>,
+}
+
+/// ServiceAccountKubeconfig configures the ServiceAccount created in the workload cluster for Fleet,
+/// and the lifetime of its token.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAccountKubeconfig {
+    /// Namespace of the ServiceAccount in the workload cluster. Defaults to `kube-system`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+
+    /// Token lifetime in seconds. The kubeconfig is rotated after two thirds of the lifetime.
+    /// Defaults to 24 hours.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 600))]
+    pub expiration_seconds: Option<i64>,
+}
+
+impl ServiceAccountKubeconfig {
+    pub(crate) fn namespace(&self) -> String {
+        self.namespace
+            .clone()
+            .unwrap_or_else(|| SERVICE_ACCOUNT_NAMESPACE.to_string())
+    }
+
+    pub(crate) fn expiration_seconds(&self) -> i64 {
+        self.expiration_seconds
+            .unwrap_or(SERVICE_ACCOUNT_TOKEN_EXPIRATION)
+    }
+}
+
+/// AdoptionPolicy controls pre-existing Fleet clusters, registered by Rancher or created manually.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, JsonSchema, Default, PartialEq)]
+pub enum AdoptionPolicy {
+    /// Take over the existing Fleet Cluster, marking it as managed by the addon provider. The Fleet
+    /// Cluster spec is overwritten, and it is deleted along with the CAPI Cluster.
+    Adopt,
+    /// Leave the existing Fleet Cluster untouched, and report the conflict on the CAPI Cluster.
+    #[default]
+    Refuse,
+    /// Import the CAPI Cluster under a different, unique Fleet Cluster name.
+    Rename,
+}
+
+/// Drain configures the removal of Fleet workloads from a deleted cluster.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Drain {
+    /// Maximum time in seconds to wait for the cluster BundleDeployments removal, counted
+    /// from the CAPI Cluster deletion. Defaults to 10 minutes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 0))]
+    pub timeout_seconds: Option<i64>,
+}
+
+impl Drain {
+    pub(crate) fn timeout_seconds(&self) -> i64 {
+        self.timeout_seconds.unwrap_or(DRAIN_TIMEOUT)
+    }
+
+    /// Check if the drain started at the given time exceeded the timeout.
+    pub(crate) fn expired(&self, started: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        now - started >= chrono::Duration::seconds(self.timeout_seconds())
+    }
+}
+
+/// SyncRules select the label or annotation keys copied to the Fleet resources. Without rules all keys are copied.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncRules {
+    /// Glob patterns of the keys to copy, e.g. `team.example.com/*`. All keys are copied if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include: Option<Vec<String>>,
+
+    /// Glob patterns of the keys to skip, taking precedence over `include`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<Vec<String>>,
+
+    /// Key prefix rewrites, applied to the copied keys. The first matching rewrite is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rewrite: Option<Vec<PrefixRewrite>>,
+}
+
+/// PrefixRewrite replaces the key prefix, e.g. `team.example.com/*` with `fleet.example.com/*`.
+/// The trailing `*` is optional.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, PartialEq)]
+pub struct PrefixRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+impl SyncRules {
+    /// Filter and rename the keys according to the rules.
+    pub fn apply(&self, source: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+        source
+            .iter()
+            .filter(|(key, _)| {
+                self.include
+                    .as_ref()
+                    .is_none_or(|include| include.iter().any(|p| glob_match(p, key)))
+            })
+            .filter(|(key, _)| !self.exclude.iter().flatten().any(|p| glob_match(p, key)))
+            .map(|(key, value)| (self.rewrite_key(key), value.clone()))
+            .collect()
+    }
+
+    fn rewrite_key(&self, key: &str) -> String {
+        self.rewrite
+            .iter()
+            .flatten()
+            .find_map(|rewrite| {
+                let from = rewrite.from.trim_end_matches('*');
+                let to = rewrite.to.trim_end_matches('*');
+                key.strip_prefix(from).map(|rest| format!("{to}{rest}"))
+            })
+            .unwrap_or_else(|| key.to_string())
+    }
+}
+
+/// Match the value against a glob pattern, supporting `*` and `?` wildcards.
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let (pattern, value): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), value.chars().collect());
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack = None;
+    while v < value.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, v));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == value[v] => {
+                p += 1;
+                v += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    v = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
 }
 
 /// NamingStrategy is controlling Fleet cluster naming
@@ -239,12 +675,18 @@ pub struct NamingStrategy {
     pub prefix: Option<String>,
     /// Specify a suffix for the Cluster name, applied to created Fleet cluster
     pub suffix: Option<String>,
+    /// Template for the Cluster name, e.g. `{{ .namespace }}-{{ .name }}`. Supported variables are
+    /// `.name`, `.namespace`, `.class` and `.classNamespace`. Prefix and suffix are applied to the rendered name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
 }
 
 impl Default for ClusterConfig {
     fn default() -> Self {
         Self {
             apply_class_group: Some(true),
+            apply_provider_groups: None,
+            apply_version_groups: None,
             set_owner_references: Some(true),
             naming: Default::default(),
             agent_namespace: AGENT_NAMESPACE.to_string().into(),
@@ -252,8 +694,19 @@ impl Default for ClusterConfig {
             #[cfg(feature = "agent-initiated")]
             agent_initiated: Some(true),
             selectors: Default::default(),
+            readiness: None,
+            label_sync: None,
+            annotation_sync: None,
+            service_account_kubeconfig: None,
+            drain: None,
+            adoption_policy: None,
+            pause_fleet_cluster: None,
             patch_resource: Some(true),
             agent_env_vars: None,
+            agent_affinity: None,
+            agent_resources: None,
+            private_repo_url: None,
+            redeploy_agent_generation: None,
             agent_tolerations: None,
         }
     }
@@ -380,6 +833,140 @@ pub struct FleetInstall {
     /// Chart version to install
     #[serde(flatten)]
     pub install_version: Install,
+
+    /// Chart repository to install Fleet charts from.
+    /// Defaults to the public https://rancher.github.io/fleet-helm-charts/ repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<ChartRepository>,
+
+    /// Helm values for the `fleet` chart
+    #[serde(flatten)]
+    pub values: ChartValues,
+
+    /// Helm values for the `fleet-crd` chart
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crd: Option<ChartValues>,
+
+    /// Remove imported Fleet clusters and uninstall `fleet` and `fleet-crd` releases
+    /// when the FleetAddonConfig is deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uninstall_on_delete: Option<bool>,
+
+    /// Time in seconds for the fleet-controller to become available after an upgrade,
+    /// before the release is rolled back. Defaults to 60 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 0))]
+    pub rollout_timeout_seconds: Option<i64>,
+}
+
+/// ChartValues are user supplied Helm chart values.
+///
+/// Values from `valuesFrom` sources are merged in the order of appearance,
+/// and `values` are merged last, taking precedence over referenced values.
+#[derive(Clone, Default, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartValues {
+    /// Inline chart values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(schema_with = "preserve_unknown_fields")]
+    pub values: Option<serde_json::Value>,
+
+    /// References to ConfigMaps or Secrets containing chart values. Changes to sources labeled
+    /// with `chart-values.fleet.addons.cluster.x-k8s.io` trigger a chart upgrade.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values_from: Option<Vec<ValuesReference>>,
+}
+
+/// ValuesReference points to a ConfigMap or Secret key containing chart values in YAML format.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValuesReference {
+    /// The reference to a ConfigMap or Secret resource
+    #[serde(rename = "ref")]
+    pub reference: ObjectReference,
+
+    /// Data key with the values. Defaults to `values.yaml`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values_key: Option<String>,
+}
+
+impl ValuesReference {
+    pub(crate) fn values_key(&self) -> &str {
+        self.values_key.as_deref().unwrap_or("values.yaml")
+    }
+
+    pub(crate) fn is_secret(&self) -> bool {
+        self.reference.kind.as_deref() == Some("Secret")
+    }
+}
+
+impl ChartValues {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.values.is_none() && self.values_from.as_ref().is_none_or(Vec::is_empty)
+    }
+}
+
+fn preserve_unknown_fields(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    serde_json::from_value(serde_json::json!({
+        "type": "object",
+        "x-kubernetes-preserve-unknown-fields": true
+    }))
+    .unwrap()
+}
+
+/// Recursively merge chart values, with the `source` values taking precedence.
+pub(crate) fn merge_values(target: &mut serde_json::Value, source: serde_json::Value) {
+    match (target, source) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(source)) => {
+            for (key, value) in source {
+                merge_values(target.entry(key).or_insert(serde_json::Value::Null), value);
+            }
+        }
+        (target, source) => *target = source,
+    }
+}
+
+/// ChartRepository describes a location of the Fleet charts.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartRepository {
+    /// Repository URL. Either a HTTP(S) chart repository, or an OCI registry
+    /// location containing `fleet` and `fleet-crd` charts, like `oci://registry.example.com/charts`.
+    pub url: String,
+
+    /// Name of the local helm repository entry. Ignored for OCI registries.
+    /// Defaults to `fleet`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Reference to a Secret with repository credentials and CA bundle.
+    /// Recognized keys are `username`, `password` and `ca.crt`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<ObjectReference>,
+
+    /// Skip TLS certificate verification of the repository server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insecure_skip_tls_verify: Option<bool>,
+}
+
+impl ChartRepository {
+    /// Name of the local helm repository entry.
+    pub(crate) fn repository_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or(FLEET_REPOSITORY_NAME.to_string())
+    }
+}
+
+impl Default for ChartRepository {
+    fn default() -> Self {
+        Self {
+            url: FLEET_REPOSITORY_URL.to_string(),
+            name: Some(FLEET_REPOSITORY_NAME.to_string()),
+            secret_ref: None,
+            insecure_skip_tls_verify: None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
@@ -390,13 +977,17 @@ pub enum Install {
 
     /// Use specific version to install
     Version(String),
+
+    /// Install the highest chart version matching the semver range constraint, e.g. `>=0.12.0, <0.13.0`
+    /// Constraints are not supported for OCI chart repositories, which can't list chart versions.
+    Constraint(String),
 }
 
 impl Install {
     /// Perform version normalization for comparison with `helm search` app_version output
     pub(crate) fn normalized(self) -> Self {
         match self {
-            Install::FollowLatest(_) => self,
+            Install::FollowLatest(_) | Install::Constraint(_) => self,
             Install::Version(version) => {
                 Install::Version(version.strip_prefix("v").unwrap_or(&version).into())
             }
@@ -424,7 +1015,38 @@ pub struct InstallOptions {
     pub api_server_url: Option<String>,
 }
 
+/// Maximum length of generated Fleet resource names, keeping them usable as label values.
+pub const MAX_NAME_LENGTH: usize = 63;
+
+/// Length of the name hash suffix appended to truncated names.
+const NAME_HASH_LENGTH: usize = 8;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum NamingError {
+    #[error("unterminated template expression in `{0}`")]
+    Unterminated(String),
+
+    #[error("unknown template variable `{0}`")]
+    UnknownVariable(String),
+
+    #[error("name `{0}` is not a valid DNS-1123 subdomain")]
+    InvalidName(String),
+}
+
 impl NamingStrategy {
+    /// Render the Fleet cluster name from the template and the provided variables, applying
+    /// prefix and suffix. Names longer than 63 characters are truncated with a hash suffix.
+    pub fn render(&self, variables: &BTreeMap<&str, String>) -> Result<String, NamingError> {
+        let name = match &self.template {
+            Some(template) => render_template(template, variables)?,
+            None => variables.get("name").cloned().unwrap_or_default(),
+        };
+
+        let name = truncate_name(self.apply(name.into()).unwrap_or_default());
+        validate_name(&name)?;
+        Ok(name)
+    }
+
     pub fn apply(&self, name: Option<String>) -> Option<String> {
         name.map(|name| match &self.prefix {
             Some(prefix) => prefix.clone() + &name,
@@ -437,6 +1059,84 @@ impl NamingStrategy {
     }
 }
 
+/// Render `{{ .variable }}` expressions in the template.
+fn render_template(
+    template: &str,
+    variables: &BTreeMap<&str, String>,
+) -> Result<String, NamingError> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            return Err(NamingError::Unterminated(template.to_string()));
+        };
+
+        let expression = rest[start + 2..start + end].trim();
+        let value = expression
+            .strip_prefix('.')
+            .and_then(|variable| variables.get(variable))
+            .ok_or_else(|| NamingError::UnknownVariable(expression.to_string()))?;
+        rendered.push_str(value);
+        rest = &rest[start + end + 2..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Shorten the name to the maximum length, replacing the tail with a hash
+/// of the full name, so distinct long names stay distinct.
+pub fn truncate_name(name: String) -> String {
+    if name.len() <= MAX_NAME_LENGTH {
+        return name;
+    }
+
+    let hash = format!("{:x}", Sha256::digest(name.as_bytes()));
+    let mut truncated: String = name
+        .chars()
+        .take(MAX_NAME_LENGTH - NAME_HASH_LENGTH - 1)
+        .collect();
+    while truncated.ends_with(['-', '.']) {
+        truncated.pop();
+    }
+
+    format!("{truncated}-{}", &hash[..NAME_HASH_LENGTH])
+}
+
+/// Derive a unique name from the given one, with a suffix based on the seed hash, within the name length limit.
+pub fn unique_name(name: &str, seed: &str) -> String {
+    let hash = format!("{:x}", Sha256::digest(seed.as_bytes()));
+    let mut base: String = name
+        .chars()
+        .take(MAX_NAME_LENGTH - NAME_HASH_LENGTH - 1)
+        .collect();
+    while base.ends_with(['-', '.']) {
+        base.pop();
+    }
+
+    format!("{base}-{}", &hash[..NAME_HASH_LENGTH])
+}
+
+/// Check the name is a valid DNS-1123 subdomain.
+pub fn validate_name(name: &str) -> Result<(), NamingError> {
+    let valid = !name.is_empty()
+        && name.len() <= 253
+        && name.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+                && !part.starts_with('-')
+                && !part.ends_with('-')
+        });
+
+    match valid {
+        true => Ok(()),
+        false => Err(NamingError::InvalidName(name.to_string())),
+    }
+}
+
 /// Selectors is controlling Fleet import strategy settings.
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default)]
 #[serde(rename_all = "camelCase")]
@@ -448,46 +1148,131 @@ pub struct Selectors {
     pub selector: LabelSelector,
 }
 
+impl Selectors {
+    /// Check if the cluster matches the cluster selector, or the cluster namespace matches the namespace selector.
+    /// An empty selector is treated as unset, so only the other selector is evaluated. Without any selector
+    /// every cluster matches.
+    pub(crate) fn matches(
+        &self,
+        cluster_labels: &BTreeMap<String, String>,
+        namespace_labels: &BTreeMap<String, String>,
+    ) -> Result<bool, ParseExpressionError> {
+        let selector: Selector = self.selector.clone().try_into()?;
+        let namespace_selector: Selector = self.namespace_selector.clone().try_into()?;
+        Ok(
+            match (selector.selects_all(), namespace_selector.selects_all()) {
+                (true, true) => true,
+                (false, true) => selector.matches(cluster_labels),
+                (true, false) => namespace_selector.matches(namespace_labels),
+                (false, false) => {
+                    selector.matches(cluster_labels) || namespace_selector.matches(namespace_labels)
+                }
+            },
+        )
+    }
+}
+
 impl FleetAddonConfig {
-    // Raw cluster selector
-    pub(crate) fn cluster_selector(&self) -> Result<Selector, ParseExpressionError> {
+    /// Import profiles in evaluation order, followed by the default `cluster` settings.
+    pub(crate) fn import_profiles(&self) -> Vec<(&str, &ClusterConfig)> {
         self.spec
-            .cluster
-            .as_ref()
-            .map(|c| c.selectors.selector.clone())
-            .unwrap_or_default()
-            .try_into()
+            .profiles
+            .iter()
+            .flatten()
+            .map(|profile| (profile.name.as_str(), &profile.config))
+            .chain(
+                self.spec
+                    .cluster
+                    .as_ref()
+                    .map(|cluster| (DEFAULT_PROFILE, cluster)),
+            )
+            .collect()
     }
 
-    // Raw namespace selector
-    pub(crate) fn namespace_selector(&self) -> Result<Selector, ParseExpressionError> {
+    /// Label sync rules for the ClusterGroup labels inherited from the ClusterClass.
+    pub(crate) fn group_label_sync(&self) -> SyncRules {
         self.spec
             .cluster
             .as_ref()
-            .map(|c| c.selectors.namespace_selector.clone())
+            .and_then(|cluster| cluster.label_sync.clone())
             .unwrap_or_default()
-            .try_into()
+    }
+
+    /// Select the first import profile matching the cluster or the cluster namespace labels.
+    pub(crate) fn import_profile(
+        &self,
+        cluster_labels: &BTreeMap<String, String>,
+        namespace_labels: &BTreeMap<String, String>,
+    ) -> Result<Option<(&str, &ClusterConfig)>, ParseExpressionError> {
+        for (name, config) in self.import_profiles() {
+            if config.selectors.matches(cluster_labels, namespace_labels)? {
+                return Ok(Some((name, config)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Raw cluster selectors of all import profiles
+    pub(crate) fn cluster_selectors(&self) -> Result<Vec<Selector>, ParseExpressionError> {
+        self.import_profiles()
+            .into_iter()
+            .map(|(_, c)| c.selectors.selector.clone().try_into())
+            .collect()
+    }
+
+    // Raw namespace selectors of all import profiles
+    pub(crate) fn namespace_selectors(&self) -> Result<Vec<Selector>, ParseExpressionError> {
+        self.import_profiles()
+            .into_iter()
+            .map(|(_, c)| c.selectors.namespace_selector.clone().try_into())
+            .collect()
     }
 
     // Check for general cluster operations, like create, patch, etc. Evaluates to false if disabled.
     pub(crate) fn cluster_operations_enabled(&self) -> bool {
-        self.spec.cluster.is_some()
+        !self.import_profiles().is_empty()
     }
 
-    // Check for general ClusterClass operations, like create, patch, etc. Evaluates to false if disabled.
-    pub(crate) fn cluster_class_operations_enabled(&self) -> bool {
-        self.spec.cluster_class.is_some()
+    pub(crate) fn deimport_policy(&self) -> DeimportPolicy {
+        self.spec.deimport_policy.unwrap_or_default()
+    }
+
+    // Check if changes to Fleet resources should only be planned, without applying them.
+    pub(crate) fn dry_run_enabled(&self) -> bool {
+        self.spec.dry_run.unwrap_or_default()
     }
 
-    // Check for general cluster patching setting.
-    pub(crate) fn cluster_patch_enabled(&self) -> bool {
+    // Check if Fleet charts should be uninstalled on FleetAddonConfig removal.
+    pub(crate) fn uninstall_on_delete(&self) -> bool {
         self.spec
-            .cluster
+            .install
             .as_ref()
-            .map(|c| c.patch_resource)
+            .and_then(|install| install.uninstall_on_delete)
             .unwrap_or_default()
-            .filter(|&enabled| enabled)
-            .is_some()
+    }
+
+    // Time for the fleet-controller rollout after an upgrade, before the release is rolled back.
+    pub(crate) fn rollout_timeout_seconds(&self) -> i64 {
+        self.spec
+            .install
+            .as_ref()
+            .and_then(|install| install.rollout_timeout_seconds)
+            .unwrap_or(ROLLOUT_TIMEOUT)
+    }
+
+    // Time since the last agent check-in, after which the agent is considered disconnected.
+    pub(crate) fn agent_disconnect_timeout(&self) -> chrono::Duration {
+        chrono::Duration::seconds(
+            self.spec
+                .agent_disconnect_timeout_seconds
+                .unwrap_or(AGENT_DISCONNECT_TIMEOUT),
+        )
+    }
+
+    // Check for general ClusterClass operations, like create, patch, etc. Evaluates to false if disabled.
+    pub(crate) fn cluster_class_operations_enabled(&self) -> bool {
+        self.spec.cluster_class.is_some()
     }
 
     // Check for general clusterClass patching setting.
@@ -504,10 +1289,17 @@ impl FleetAddonConfig {
 
 #[cfg(test)]
 mod tests {
-    use std::str::FromStr;
+    use std::{collections::BTreeMap, str::FromStr};
+
+    use chrono::Utc;
+    use serde_json::json;
+
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
 
     use crate::api::fleet_addon_config::{
-        FeatureGates, FleetChartValues, FleetSettingsSpec, NamingStrategy,
+        merge_values, truncate_name, ClusterConfig, Drain, FeatureGates, FleetAddonConfig,
+        FleetAddonConfigSpec, FleetChartValues, FleetSettingsSpec, ImportProfile, NamingError,
+        NamingStrategy, PrefixRewrite, Selectors, SyncRules, MAX_NAME_LENGTH,
     };
 
     #[tokio::test]
@@ -517,6 +1309,7 @@ mod tests {
             NamingStrategy {
                 prefix: "prefix".to_string().into(),
                 suffix: "suffix".to_string().into(),
+                ..Default::default()
             }
             .apply("test".to_string().into())
         );
@@ -552,11 +1345,107 @@ mod tests {
             NamingStrategy {
                 prefix: "prefix".to_string().into(),
                 suffix: "suffix".to_string().into(),
+                ..Default::default()
             }
             .apply(None)
         );
     }
 
+    #[test]
+    fn test_sync_rules() {
+        let labels = BTreeMap::from([
+            ("team.example.com/owner".to_string(), "a".to_string()),
+            ("team.example.com/internal".to_string(), "b".to_string()),
+            (
+                "kubectl.kubernetes.io/last-applied-configuration".to_string(),
+                "{}".to_string(),
+            ),
+            ("env".to_string(), "prod".to_string()),
+        ]);
+
+        assert_eq!(labels, SyncRules::default().apply(&labels));
+
+        let rules = SyncRules {
+            include: Some(vec!["team.example.com/*".into(), "e?v".into()]),
+            exclude: Some(vec!["*/internal".into()]),
+            rewrite: Some(vec![PrefixRewrite {
+                from: "team.example.com/*".into(),
+                to: "fleet.example.com/*".into(),
+            }]),
+        };
+        assert_eq!(
+            BTreeMap::from([
+                ("fleet.example.com/owner".to_string(), "a".to_string()),
+                ("env".to_string(), "prod".to_string()),
+            ]),
+            rules.apply(&labels)
+        );
+    }
+
+    #[test]
+    fn test_drain_expired() {
+        let started = Utc::now();
+        let drain = Drain::default();
+        assert!(!drain.expired(started, started + chrono::Duration::seconds(599)));
+        assert!(drain.expired(started, started + chrono::Duration::seconds(600)));
+
+        let drain = Drain {
+            timeout_seconds: Some(0),
+        };
+        assert!(drain.expired(started, started));
+    }
+
+    #[test]
+    fn test_naming_template() {
+        let variables = BTreeMap::from([
+            ("name", "cluster".to_string()),
+            ("namespace", "default".to_string()),
+        ]);
+
+        let strategy = NamingStrategy {
+            template: Some("{{ .namespace }}-{{.name}}".to_string()),
+            suffix: Some("-fleet".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Ok("default-cluster-fleet".to_string()),
+            strategy.render(&variables)
+        );
+
+        let strategy = NamingStrategy {
+            template: Some("{{ .class }}-{{ .name".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            Err(NamingError::UnknownVariable(".class".to_string())),
+            strategy.render(&variables)
+        );
+
+        let strategy = NamingStrategy {
+            template: Some("{{ .name }}-{{ .name".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            strategy.render(&variables),
+            Err(NamingError::Unterminated(_))
+        ));
+
+        let strategy = NamingStrategy {
+            prefix: Some("Upper_".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            strategy.render(&variables),
+            Err(NamingError::InvalidName(_))
+        ));
+
+        let long = "a".repeat(70);
+        let truncated = truncate_name(long.clone());
+        assert_eq!(MAX_NAME_LENGTH, truncated.len());
+        assert_ne!(truncated, truncate_name(long + "b"));
+        assert_eq!("short", truncate_name("short".to_string()));
+    }
+
     #[tokio::test]
     async fn test_sync_config_map() {
         let want_fleet_data = r#"extraEnv:
@@ -607,4 +1496,136 @@ foo:
 
         assert_eq!(want_fleet_data.to_string(), data.fleet.to_string())
     }
+
+    #[test]
+    fn test_merge_values() {
+        let mut values = json!({
+            "replicas": 1,
+            "image": {"repository": "rancher/fleet", "tag": "v0.12.0"},
+            "tolerations": [{"key": "a"}],
+        });
+
+        merge_values(
+            &mut values,
+            json!({
+                "image": {"tag": "v0.12.1"},
+                "tolerations": [{"key": "b"}],
+                "systemDefaultRegistry": "registry.example.com",
+            }),
+        );
+
+        assert_eq!(
+            values,
+            json!({
+                "replicas": 1,
+                "image": {"repository": "rancher/fleet", "tag": "v0.12.1"},
+                "tolerations": [{"key": "b"}],
+                "systemDefaultRegistry": "registry.example.com",
+            })
+        );
+    }
+
+    #[test]
+    fn test_import_profile() {
+        let selectors = |key: &str| Selectors {
+            selector: LabelSelector {
+                match_labels: Some(BTreeMap::from([(key.into(), "true".into())])),
+                ..Default::default()
+            },
+            namespace_selector: LabelSelector {
+                match_labels: Some(BTreeMap::from([(key.into(), "true".into())])),
+                ..Default::default()
+            },
+        };
+        let profile = |name: &str| ImportProfile {
+            name: name.into(),
+            config: ClusterConfig {
+                selectors: selectors(name),
+                ..Default::default()
+            },
+        };
+
+        let config = FleetAddonConfig {
+            spec: FleetAddonConfigSpec {
+                profiles: Some(vec![profile("gpu"), profile("edge")]),
+                cluster: Some(ClusterConfig {
+                    selectors: selectors("import"),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let labels = |keys: &[&str]| -> BTreeMap<String, String> {
+            keys.iter()
+                .map(|k| (k.to_string(), "true".into()))
+                .collect()
+        };
+        let profile_name = |cluster: &[&str], namespace: &[&str]| {
+            config
+                .import_profile(&labels(cluster), &labels(namespace))
+                .unwrap()
+                .map(|(name, _)| name.to_string())
+        };
+
+        assert_eq!(profile_name(&["edge", "gpu"], &[]), Some("gpu".into()));
+        assert_eq!(profile_name(&[], &["edge"]), Some("edge".into()));
+        assert_eq!(profile_name(&["import"], &[]), Some("default".into()));
+        assert_eq!(profile_name(&["other"], &["other"]), None);
+        assert_eq!(config.cluster_selectors().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_import_profile_partial_selectors() {
+        let match_labels = |key: &str| LabelSelector {
+            match_labels: Some(BTreeMap::from([(key.into(), "true".into())])),
+            ..Default::default()
+        };
+        let profile = |name: &str, selectors: Selectors| ImportProfile {
+            name: name.into(),
+            config: ClusterConfig {
+                selectors,
+                ..Default::default()
+            },
+        };
+
+        // The first profile sets only the cluster selector, leaving the namespace selector empty
+        let config = FleetAddonConfig {
+            spec: FleetAddonConfigSpec {
+                profiles: Some(vec![
+                    profile(
+                        "gpu",
+                        Selectors {
+                            selector: match_labels("gpu"),
+                            namespace_selector: Default::default(),
+                        },
+                    ),
+                    profile(
+                        "edge",
+                        Selectors {
+                            selector: Default::default(),
+                            namespace_selector: match_labels("edge"),
+                        },
+                    ),
+                ]),
+                cluster: Some(Default::default()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let labels = |key: &str| BTreeMap::from([(key.to_string(), "true".to_string())]);
+        let profile_name = |cluster, namespace| {
+            config
+                .import_profile(&labels(cluster), &labels(namespace))
+                .unwrap()
+                .map(|(name, _)| name.to_string())
+        };
+
+        assert_eq!(profile_name("gpu", "edge"), Some("gpu".into()));
+        assert_eq!(profile_name("other", "edge"), Some("edge".into()));
+        // Default settings without selectors match every cluster
+        assert_eq!(profile_name("other", "other"), Some("default".into()));
+    }
 }
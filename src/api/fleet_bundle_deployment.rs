@@ -4,31 +4,48 @@
 
 #[allow(unused_imports)]
 mod prelude {
+    pub use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
     pub use kube::CustomResource;
     pub use schemars::JsonSchema;
-    pub use serde::{Serialize, Deserialize};
+    pub use serde::{Deserialize, Serialize};
     pub use std::collections::BTreeMap;
-    pub use k8s_openapi::apimachinery::pkg::apis::meta::v1::Condition;
 }
 use self::prelude::*;
 
 #[derive(CustomResource, Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
-#[kube(group = "fleet.cattle.io", version = "v1alpha1", kind = "BundleDeployment", plural = "bundledeployments")]
+#[kube(
+    group = "fleet.cattle.io",
+    version = "v1alpha1",
+    kind = "BundleDeployment",
+    plural = "bundledeployments"
+)]
 #[kube(namespaced)]
 #[kube(status = "BundleDeploymentStatus")]
-#[kube(derive="Default")]
+#[kube(derive = "Default")]
 pub struct BundleDeploymentSpec {
     /// CorrectDrift specifies how drift correction should work.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "correctDrift")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "correctDrift"
+    )]
     pub correct_drift: Option<BundleDeploymentCorrectDrift>,
     /// DependsOn refers to the bundles which must be ready before this bundle can be deployed.
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "dependsOn")]
     pub depends_on: Option<Vec<BundleDeploymentDependsOn>>,
     /// DeploymentID is the ID of the currently applied deployment.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "deploymentID")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "deploymentID"
+    )]
     pub deployment_id: Option<String>,
     /// OCIContents is true when this deployment's contents is stored in an oci registry
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "ociContents")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "ociContents"
+    )]
     pub oci_contents: Option<bool>,
     /// Options are the deployment options, that are currently applied.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -39,11 +56,19 @@ pub struct BundleDeploymentSpec {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub paused: Option<bool>,
     /// StagedDeploymentID is the ID of the staged deployment.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "stagedDeploymentID")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "stagedDeploymentID"
+    )]
     pub staged_deployment_id: Option<String>,
     /// StagedOptions are the deployment options, that are staged for
     /// the next deployment.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "stagedOptions")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "stagedOptions"
+    )]
     pub staged_options: Option<BundleDeploymentStagedOptions>,
 }
 
@@ -57,7 +82,11 @@ pub struct BundleDeploymentCorrectDrift {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub force: Option<bool>,
     /// KeepFailHistory keeps track of failed rollbacks in the helm history.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "keepFailHistory")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "keepFailHistory"
+    )]
     pub keep_fail_history: Option<bool>,
 }
 
@@ -75,12 +104,20 @@ pub struct BundleDeploymentDependsOn {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentDependsOnSelector {
     /// matchExpressions is a list of label selector requirements. The requirements are ANDed.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "matchExpressions")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "matchExpressions"
+    )]
     pub match_expressions: Option<Vec<BundleDeploymentDependsOnSelectorMatchExpressions>>,
     /// matchLabels is a map of {key,value} pairs. A single {key,value} in the matchLabels
     /// map is equivalent to an element of matchExpressions, whose key field is "key", the
     /// operator is "In", and the values array contains only "value". The requirements are ANDed.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "matchLabels")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "matchLabels"
+    )]
     pub match_labels: Option<BTreeMap<String, String>>,
 }
 
@@ -105,24 +142,44 @@ pub struct BundleDeploymentDependsOnSelectorMatchExpressions {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentOptions {
     /// CorrectDrift specifies how drift correction should work.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "correctDrift")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "correctDrift"
+    )]
     pub correct_drift: Option<BundleDeploymentOptionsCorrectDrift>,
     /// DefaultNamespace is the namespace to use for resources that do not
     /// specify a namespace. This field is not used to enforce or lock down
     /// the deployment to a specific namespace.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "defaultNamespace")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "defaultNamespace"
+    )]
     pub default_namespace: Option<String>,
     /// DeleteCRDResources deletes CRDs. Warning! this will also delete all your Custom Resources.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "deleteCRDResources")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "deleteCRDResources"
+    )]
     pub delete_crd_resources: Option<bool>,
     /// DeleteNamespace can be used to delete the deployed namespace when removing the bundle
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "deleteNamespace")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "deleteNamespace"
+    )]
     pub delete_namespace: Option<bool>,
     /// Diff can be used to ignore the modified state of objects which are amended at runtime.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub diff: Option<BundleDeploymentOptionsDiff>,
     /// ForceSyncGeneration is used to force a redeployment
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "forceSyncGeneration")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "forceSyncGeneration"
+    )]
     pub force_sync_generation: Option<i64>,
     /// Helm options for the deployment, like the chart name, repo and values.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -131,7 +188,11 @@ pub struct BundleDeploymentOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ignore: Option<BundleDeploymentOptionsIgnore>,
     /// KeepResources can be used to keep the deployed resources when removing the bundle
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "keepResources")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "keepResources"
+    )]
     pub keep_resources: Option<bool>,
     /// Kustomize options for the deployment, like the dir containing the
     /// kustomization.yaml file.
@@ -143,13 +204,25 @@ pub struct BundleDeploymentOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub namespace: Option<String>,
     /// NamespaceAnnotations are annotations that will be appended to the namespace created by Fleet.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "namespaceAnnotations")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "namespaceAnnotations"
+    )]
     pub namespace_annotations: Option<BTreeMap<String, String>>,
     /// NamespaceLabels are labels that will be appended to the namespace created by Fleet.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "namespaceLabels")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "namespaceLabels"
+    )]
     pub namespace_labels: Option<BTreeMap<String, String>>,
     /// ServiceAccount which will be used to perform this deployment.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "serviceAccount")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "serviceAccount"
+    )]
     pub service_account: Option<String>,
     /// YAML options, if using raw YAML these are names that map to
     /// overlays/{name} files that will be used to replace or patch a resource.
@@ -167,7 +240,11 @@ pub struct BundleDeploymentOptionsCorrectDrift {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub force: Option<bool>,
     /// KeepFailHistory keeps track of failed rollbacks in the helm history.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "keepFailHistory")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "keepFailHistory"
+    )]
     pub keep_fail_history: Option<bool>,
 }
 
@@ -175,7 +252,11 @@ pub struct BundleDeploymentOptionsCorrectDrift {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentOptionsDiff {
     /// ComparePatches match a resource and remove fields from the check for modifications.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "comparePatches")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "comparePatches"
+    )]
     pub compare_patches: Option<Vec<BundleDeploymentOptionsDiffComparePatches>>,
 }
 
@@ -183,10 +264,18 @@ pub struct BundleDeploymentOptionsDiff {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentOptionsDiffComparePatches {
     /// APIVersion is the apiVersion of the resource to match.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "apiVersion")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "apiVersion"
+    )]
     pub api_version: Option<String>,
     /// JSONPointers ignore diffs at a certain JSON path.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "jsonPointers")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "jsonPointers"
+    )]
     pub json_pointers: Option<Vec<String>>,
     /// Kind is the kind of the resource to match.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -227,46 +316,86 @@ pub struct BundleDeploymentOptionsHelm {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub chart: Option<String>,
     /// DisableDNS can be used to customize Helm's EnableDNS option, which Fleet sets to `true` by default.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "disableDNS")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "disableDNS"
+    )]
     pub disable_dns: Option<bool>,
     /// DisableDependencyUpdate allows skipping chart dependencies update
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "disableDependencyUpdate")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "disableDependencyUpdate"
+    )]
     pub disable_dependency_update: Option<bool>,
     /// DisablePreProcess disables template processing in values
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "disablePreProcess")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "disablePreProcess"
+    )]
     pub disable_pre_process: Option<bool>,
     /// Force allows to override immutable resources. This could be dangerous.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub force: Option<bool>,
     /// MaxHistory limits the maximum number of revisions saved per release by Helm.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "maxHistory")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "maxHistory"
+    )]
     pub max_history: Option<i64>,
     /// ReleaseName sets a custom release name to deploy the chart as. If
     /// not specified a release name will be generated by combining the
     /// invoking GitRepo.name + GitRepo.path.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "releaseName")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "releaseName"
+    )]
     pub release_name: Option<String>,
     /// Repo is the name of the HTTPS helm repo to download the chart from.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub repo: Option<String>,
     /// SkipSchemaValidation allows skipping schema validation against the chart values
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "skipSchemaValidation")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "skipSchemaValidation"
+    )]
     pub skip_schema_validation: Option<bool>,
     /// TakeOwnership makes helm skip the check for its own annotations
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "takeOwnership")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "takeOwnership"
+    )]
     pub take_ownership: Option<bool>,
     /// TimeoutSeconds is the time to wait for Helm operations.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "timeoutSeconds")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "timeoutSeconds"
+    )]
     pub timeout_seconds: Option<i64>,
     /// Values passed to Helm. It is possible to specify the keys and values
     /// as go template strings.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub values: Option<BTreeMap<String, serde_json::Value>>,
     /// ValuesFiles is a list of files to load values from.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "valuesFiles")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "valuesFiles"
+    )]
     pub values_files: Option<Vec<String>>,
     /// ValuesFrom loads the values from configmaps and secrets.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "valuesFrom")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "valuesFrom"
+    )]
     pub values_from: Option<Vec<BundleDeploymentOptionsHelmValuesFrom>>,
     /// Version of the chart to download
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -274,7 +403,11 @@ pub struct BundleDeploymentOptionsHelm {
     /// WaitForJobs if set and timeoutSeconds provided, will wait until all
     /// Jobs have been completed before marking the GitRepo as ready. It
     /// will wait for as long as timeoutSeconds
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "waitForJobs")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "waitForJobs"
+    )]
     pub wait_for_jobs: Option<bool>,
 }
 
@@ -282,10 +415,18 @@ pub struct BundleDeploymentOptionsHelm {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentOptionsHelmValuesFrom {
     /// The reference to a config map with release values.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "configMapKeyRef")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "configMapKeyRef"
+    )]
     pub config_map_key_ref: Option<BundleDeploymentOptionsHelmValuesFromConfigMapKeyRef>,
     /// The reference to a secret with release values.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "secretKeyRef")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "secretKeyRef"
+    )]
     pub secret_key_ref: Option<BundleDeploymentOptionsHelmValuesFromSecretKeyRef>,
 }
 
@@ -349,24 +490,44 @@ pub struct BundleDeploymentOptionsYaml {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStagedOptions {
     /// CorrectDrift specifies how drift correction should work.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "correctDrift")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "correctDrift"
+    )]
     pub correct_drift: Option<BundleDeploymentStagedOptionsCorrectDrift>,
     /// DefaultNamespace is the namespace to use for resources that do not
     /// specify a namespace. This field is not used to enforce or lock down
     /// the deployment to a specific namespace.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "defaultNamespace")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "defaultNamespace"
+    )]
     pub default_namespace: Option<String>,
     /// DeleteCRDResources deletes CRDs. Warning! this will also delete all your Custom Resources.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "deleteCRDResources")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "deleteCRDResources"
+    )]
     pub delete_crd_resources: Option<bool>,
     /// DeleteNamespace can be used to delete the deployed namespace when removing the bundle
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "deleteNamespace")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "deleteNamespace"
+    )]
     pub delete_namespace: Option<bool>,
     /// Diff can be used to ignore the modified state of objects which are amended at runtime.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub diff: Option<BundleDeploymentStagedOptionsDiff>,
     /// ForceSyncGeneration is used to force a redeployment
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "forceSyncGeneration")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "forceSyncGeneration"
+    )]
     pub force_sync_generation: Option<i64>,
     /// Helm options for the deployment, like the chart name, repo and values.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -375,7 +536,11 @@ pub struct BundleDeploymentStagedOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ignore: Option<BundleDeploymentStagedOptionsIgnore>,
     /// KeepResources can be used to keep the deployed resources when removing the bundle
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "keepResources")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "keepResources"
+    )]
     pub keep_resources: Option<bool>,
     /// Kustomize options for the deployment, like the dir containing the
     /// kustomization.yaml file.
@@ -387,13 +552,25 @@ pub struct BundleDeploymentStagedOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub namespace: Option<String>,
     /// NamespaceAnnotations are annotations that will be appended to the namespace created by Fleet.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "namespaceAnnotations")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "namespaceAnnotations"
+    )]
     pub namespace_annotations: Option<BTreeMap<String, String>>,
     /// NamespaceLabels are labels that will be appended to the namespace created by Fleet.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "namespaceLabels")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "namespaceLabels"
+    )]
     pub namespace_labels: Option<BTreeMap<String, String>>,
     /// ServiceAccount which will be used to perform this deployment.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "serviceAccount")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "serviceAccount"
+    )]
     pub service_account: Option<String>,
     /// YAML options, if using raw YAML these are names that map to
     /// overlays/{name} files that will be used to replace or patch a resource.
@@ -411,7 +588,11 @@ pub struct BundleDeploymentStagedOptionsCorrectDrift {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub force: Option<bool>,
     /// KeepFailHistory keeps track of failed rollbacks in the helm history.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "keepFailHistory")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "keepFailHistory"
+    )]
     pub keep_fail_history: Option<bool>,
 }
 
@@ -419,7 +600,11 @@ pub struct BundleDeploymentStagedOptionsCorrectDrift {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStagedOptionsDiff {
     /// ComparePatches match a resource and remove fields from the check for modifications.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "comparePatches")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "comparePatches"
+    )]
     pub compare_patches: Option<Vec<BundleDeploymentStagedOptionsDiffComparePatches>>,
 }
 
@@ -427,10 +612,18 @@ pub struct BundleDeploymentStagedOptionsDiff {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStagedOptionsDiffComparePatches {
     /// APIVersion is the apiVersion of the resource to match.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "apiVersion")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "apiVersion"
+    )]
     pub api_version: Option<String>,
     /// JSONPointers ignore diffs at a certain JSON path.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "jsonPointers")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "jsonPointers"
+    )]
     pub json_pointers: Option<Vec<String>>,
     /// Kind is the kind of the resource to match.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -471,46 +664,86 @@ pub struct BundleDeploymentStagedOptionsHelm {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub chart: Option<String>,
     /// DisableDNS can be used to customize Helm's EnableDNS option, which Fleet sets to `true` by default.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "disableDNS")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "disableDNS"
+    )]
     pub disable_dns: Option<bool>,
     /// DisableDependencyUpdate allows skipping chart dependencies update
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "disableDependencyUpdate")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "disableDependencyUpdate"
+    )]
     pub disable_dependency_update: Option<bool>,
     /// DisablePreProcess disables template processing in values
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "disablePreProcess")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "disablePreProcess"
+    )]
     pub disable_pre_process: Option<bool>,
     /// Force allows to override immutable resources. This could be dangerous.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub force: Option<bool>,
     /// MaxHistory limits the maximum number of revisions saved per release by Helm.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "maxHistory")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "maxHistory"
+    )]
     pub max_history: Option<i64>,
     /// ReleaseName sets a custom release name to deploy the chart as. If
     /// not specified a release name will be generated by combining the
     /// invoking GitRepo.name + GitRepo.path.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "releaseName")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "releaseName"
+    )]
     pub release_name: Option<String>,
     /// Repo is the name of the HTTPS helm repo to download the chart from.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub repo: Option<String>,
     /// SkipSchemaValidation allows skipping schema validation against the chart values
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "skipSchemaValidation")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "skipSchemaValidation"
+    )]
     pub skip_schema_validation: Option<bool>,
     /// TakeOwnership makes helm skip the check for its own annotations
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "takeOwnership")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "takeOwnership"
+    )]
     pub take_ownership: Option<bool>,
     /// TimeoutSeconds is the time to wait for Helm operations.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "timeoutSeconds")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "timeoutSeconds"
+    )]
     pub timeout_seconds: Option<i64>,
     /// Values passed to Helm. It is possible to specify the keys and values
     /// as go template strings.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub values: Option<BTreeMap<String, serde_json::Value>>,
     /// ValuesFiles is a list of files to load values from.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "valuesFiles")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "valuesFiles"
+    )]
     pub values_files: Option<Vec<String>>,
     /// ValuesFrom loads the values from configmaps and secrets.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "valuesFrom")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "valuesFrom"
+    )]
     pub values_from: Option<Vec<BundleDeploymentStagedOptionsHelmValuesFrom>>,
     /// Version of the chart to download
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -518,7 +751,11 @@ pub struct BundleDeploymentStagedOptionsHelm {
     /// WaitForJobs if set and timeoutSeconds provided, will wait until all
     /// Jobs have been completed before marking the GitRepo as ready. It
     /// will wait for as long as timeoutSeconds
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "waitForJobs")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "waitForJobs"
+    )]
     pub wait_for_jobs: Option<bool>,
 }
 
@@ -526,10 +763,18 @@ pub struct BundleDeploymentStagedOptionsHelm {
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStagedOptionsHelmValuesFrom {
     /// The reference to a config map with release values.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "configMapKeyRef")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "configMapKeyRef"
+    )]
     pub config_map_key_ref: Option<BundleDeploymentStagedOptionsHelmValuesFromConfigMapKeyRef>,
     /// The reference to a secret with release values.
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "secretKeyRef")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "secretKeyRef"
+    )]
     pub secret_key_ref: Option<BundleDeploymentStagedOptionsHelmValuesFromSecretKeyRef>,
 }
 
@@ -590,17 +835,33 @@ pub struct BundleDeploymentStagedOptionsYaml {
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStatus {
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "appliedDeploymentID")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "appliedDeploymentID"
+    )]
     pub applied_deployment_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub conditions: Option<Vec<Condition>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub display: Option<BundleDeploymentStatusDisplay>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "modifiedStatus")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "modifiedStatus"
+    )]
     pub modified_status: Option<Vec<BundleDeploymentStatusModifiedStatus>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "nonModified")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "nonModified"
+    )]
     pub non_modified: Option<bool>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "nonReadyStatus")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "nonReadyStatus"
+    )]
     pub non_ready_status: Option<Vec<BundleDeploymentStatusNonReadyStatus>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ready: Option<bool>,
@@ -610,7 +871,11 @@ pub struct BundleDeploymentStatus {
     /// according to the helm release history.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resources: Option<Vec<BundleDeploymentStatusResources>>,
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "syncGeneration")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "syncGeneration"
+    )]
     pub sync_generation: Option<i64>,
 }
 
@@ -628,7 +893,11 @@ pub struct BundleDeploymentStatusDisplay {
 /// It indicates if the modification was a create, a delete or a patch.
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStatusModifiedStatus {
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "apiVersion")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "apiVersion"
+    )]
     pub api_version: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub delete: Option<bool>,
@@ -647,7 +916,11 @@ pub struct BundleDeploymentStatusModifiedStatus {
 /// NonReadyStatus is used to report the status of a resource that is not ready. It includes a summary.
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStatusNonReadyStatus {
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "apiVersion")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "apiVersion"
+    )]
     pub api_version: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub kind: Option<String>,
@@ -679,7 +952,11 @@ pub struct BundleDeploymentStatusNonReadyStatusSummary {
 /// BundleDeploymentResource contains the metadata of a deployed resource.
 #[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
 pub struct BundleDeploymentStatusResources {
-    #[serde(default, skip_serializing_if = "Option::is_none", rename = "apiVersion")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        rename = "apiVersion"
+    )]
     pub api_version: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none", rename = "createdAt")]
     pub created_at: Option<String>,
@@ -690,4 +967,3 @@ pub struct BundleDeploymentStatusResources {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub namespace: Option<String>,
 }
-
@@ -1,10 +1,16 @@
 use fleet_api_rs::fleet_cluster::{ClusterSpec, ClusterStatus};
 use kube::{
     api::{ObjectMeta, TypeMeta},
-    Resource,
+    Resource, ResourceExt as _,
 };
 use serde::{Deserialize, Serialize};
 
+use super::capi_cluster::CLUSTER_NAME_LABEL;
+
+/// Labels set by Fleet on the BundleDeployments, referencing the target Fleet Cluster
+pub static FLEET_CLUSTER_LABEL: &str = "fleet.cattle.io/cluster";
+pub static FLEET_CLUSTER_NAMESPACE_LABEL: &str = "fleet.cattle.io/cluster-namespace";
+
 #[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 #[resource(inherit = fleet_api_rs::fleet_cluster::Cluster)]
 pub struct Cluster {
@@ -14,3 +20,18 @@ pub struct Cluster {
     pub spec: ClusterSpec,
     pub status: Option<ClusterStatus>,
 }
+
+impl Cluster {
+    /// Name of the CAPI Cluster the Fleet cluster was imported for, set in the cluster name label.
+    /// Clusters imported before the label was introduced are matched by their kubeconfig secret,
+    /// which references the CAPI admin kubeconfig of the cluster with the same name.
+    pub(crate) fn capi_cluster_name(&self) -> Option<String> {
+        if let Some(name) = self.labels().get(CLUSTER_NAME_LABEL) {
+            return Some(name.clone());
+        }
+
+        let name = self.name_any();
+        let legacy_secret = format!("{name}-kubeconfig");
+        (self.spec.kube_config_secret.as_ref() == Some(&legacy_secret)).then_some(name)
+    }
+}
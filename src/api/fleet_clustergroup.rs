@@ -18,6 +18,17 @@ pub static CLUSTER_CLASS_LABEL: &str = "clusterclass-name.fleet.addons.cluster.x
 pub static CLUSTER_CLASS_NAMESPACE_LABEL: &str =
     "clusterclass-namespace.fleet.addons.cluster.x-k8s.io";
 
+/// Labels with the infrastructure and control plane provider kinds of the CAPI cluster
+pub static INFRASTRUCTURE_KIND_LABEL: &str = "infrastructure-kind.fleet.addons.cluster.x-k8s.io";
+pub static CONTROL_PLANE_KIND_LABEL: &str = "controlplane-kind.fleet.addons.cluster.x-k8s.io";
+
+/// Labels with the desired, observed and minor Kubernetes version of the CAPI cluster
+pub static KUBERNETES_VERSION_LABEL: &str = "kubernetes-version.fleet.addons.cluster.x-k8s.io";
+pub static KUBERNETES_OBSERVED_VERSION_LABEL: &str =
+    "kubernetes-observed-version.fleet.addons.cluster.x-k8s.io";
+pub static KUBERNETES_MINOR_VERSION_LABEL: &str =
+    "kubernetes-minor-version.fleet.addons.cluster.x-k8s.io";
+
 #[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
 #[resource(inherit = fleet_api_rs::fleet_clustergroup::ClusterGroup)]
 pub struct ClusterGroup {
@@ -2,6 +2,7 @@ pub mod bundle_namespace_mapping;
 pub mod capi_cluster;
 pub mod capi_clusterclass;
 pub mod fleet_addon_config;
+pub mod fleet_bundle_deployment;
 pub mod fleet_cluster;
 #[cfg(feature = "agent-initiated")]
 pub mod fleet_cluster_registration_token;
@@ -1,24 +1,36 @@
 use crate::api::bundle_namespace_mapping::BundleNamespaceMapping;
 use crate::api::capi_cluster::Cluster;
+use crate::api::capi_cluster::CLUSTER_NAME_LABEL;
 use crate::api::capi_clusterclass::ClusterClass;
-use crate::api::fleet_addon_config::FleetAddonConfig;
-use crate::api::fleet_cluster;
-use crate::api::fleet_clustergroup::ClusterGroup;
-use crate::controllers::addon_config::FleetConfig;
-use crate::controllers::controller::{fetch_config, Context, DynamicStream, FleetController};
+use crate::api::fleet_addon_config::{FleetAddonConfig, CHART_VALUES_LABEL};
+use crate::api::fleet_bundle_deployment::BundleDeployment;
+use crate::api::fleet_cluster::{self, FLEET_CLUSTER_LABEL, FLEET_CLUSTER_NAMESPACE_LABEL};
+use crate::api::fleet_clustergroup::{
+    ClusterGroup, CONTROL_PLANE_KIND_LABEL, INFRASTRUCTURE_KIND_LABEL,
+    KUBERNETES_MINOR_VERSION_LABEL,
+};
+use crate::controllers::controller::{
+    fetch_config, Context, DynamicStream, FleetController, FLEET_FINALIZER,
+};
 use crate::metrics::Diagnostics;
 use crate::multi_dispatcher::{broadcaster, BroadcastStream, MultiDispatcher};
+use crate::predicates::deimport_settings;
 use crate::{Error, Metrics};
 
 use chrono::Local;
 use clap::Parser;
+use cluster_api_rs::capi_machine::Machine;
+use futures::lock::Mutex;
+use futures::stream::SelectAll;
 use futures::{Stream, StreamExt};
 
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, Secret};
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
 use kube::api::{Patch, PatchParams};
-use kube::core::DeserializeGuard;
+use kube::core::PartialObjectMeta;
 use kube::runtime::reflector::store::Writer;
 use kube::runtime::reflector::ObjectRef;
+use kube::runtime::reflector::Store;
 use kube::runtime::{metadata_watcher, predicates, reflector, watcher, WatchStreamExt};
 use kube::{
     api::Api,
@@ -33,9 +45,10 @@ use kube::{Resource, ResourceExt};
 use std::collections::BTreeMap;
 
 use std::ops::Deref;
+use std::pin::Pin;
 use std::sync::Arc;
 use tokio::{sync::RwLock, time::Duration};
-use tracing::{self, warn};
+use tracing::{self, info, warn};
 
 /// State shared between the controller and the web server
 #[derive(Clone)]
@@ -56,6 +69,9 @@ pub struct State {
 
     // k8s api server minor version
     pub version: u32,
+
+    // FleetAddonConfig reflector store, populated by the controllers reading the config
+    config: Store<FleetAddonConfig>,
 }
 
 #[derive(Parser, Debug, Clone, Default)]
@@ -63,6 +79,33 @@ pub struct Flags {
     /// helm install allows to select container for performing fleet chart installation
     #[arg(long)]
     pub helm_install: bool,
+
+    /// Enable Lease based leader election, ensuring only one active controller manager
+    #[arg(long)]
+    pub leader_elect: bool,
+
+    /// Name of the Lease used for leader election. Defaults to a name based on the controller mode
+    #[arg(long)]
+    pub leader_election_id: Option<String>,
+
+    /// Namespace of the leader election Lease. Defaults to the service account namespace
+    #[arg(long)]
+    pub leader_election_namespace: Option<String>,
+
+    /// Preview changes to Fleet resources without applying them, reporting planned changes as events
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl Flags {
+    /// Name of the leader election Lease for the selected controller mode
+    pub fn leader_election_id(&self) -> String {
+        match (&self.leader_election_id, self.helm_install) {
+            (Some(id), _) => id.clone(),
+            (None, true) => "caapf-helm-manager".into(),
+            (None, false) => "caapf-controller-manager".into(),
+        }
+    }
 }
 
 impl State {
@@ -76,6 +119,7 @@ impl State {
             diagnostics: Default::default(),
             stream: BroadcastStream::new(Default::default()),
             version,
+            config: Writer::default().as_reader(),
         }
     }
 
@@ -98,8 +142,24 @@ impl State {
             dispatcher: self.dispatcher.clone(),
             stream: self.stream.clone(),
             version: self.version,
+            dry_run: self.flags.dry_run,
+            config: self.config.clone(),
         })
     }
+
+    /// FleetAddonConfig watch populating the config store read by the controllers.
+    fn config_watch(
+        &mut self,
+        client: Client,
+    ) -> impl WatchStreamExt<Item = Result<FleetAddonConfig, watcher::Error>> {
+        let (reader, writer) = reflector::store();
+        self.config = reader;
+        watcher(
+            Api::<FleetAddonConfig>::all(client),
+            Config::default().any_semantic(),
+        )
+        .default_with_reflect(writer)
+    }
 }
 
 trait ControllerDefault: WatchStreamExt {
@@ -140,20 +200,37 @@ pub async fn run_fleet_addon_config_controller(state: State) {
     let config_controller = Controller::new(
         Api::<FleetAddonConfig>::all(client.clone()),
         Config::default().any_semantic(),
-    )
-    .watches(
-        Api::<DeserializeGuard<FleetConfig>>::all(client.clone()),
-        Config::default().fields("metadata.name=fleet-controller"),
-        |config| config.0.ok().map(|_| ObjectRef::new("fleet-addon-config")),
-    )
-    .shutdown_on_signal()
-    .run(
-        FleetAddonConfig::reconcile_config_sync,
-        error_policy,
-        state.to_context(client.clone()),
-    )
-    .default_backoff()
-    .for_each(|_| futures::future::ready(()));
+    );
+
+    let config = Arc::new(
+        fetch_config(client.clone())
+            .await
+            .expect("failed to get FleetAddonConfig resource"),
+    );
+
+    // Fleet controller ConfigMap watch is re-targeted when the fleet namespace or name changes
+    let fleet_config_watch = FleetConfigWatch::new(client.clone(), &config);
+    let fleet_configs = fleet_config_watch.stream.clone().default_handling();
+
+    let store = config_controller.store();
+    let config_controller = config_controller
+        .watches_stream(fleet_configs, move |config_map| {
+            fleet_config_references(&store, config_map)
+        })
+        .shutdown_on_signal()
+        .run(
+            move |config, ctx| {
+                let fleet_config_watch = fleet_config_watch.clone();
+                async move {
+                    fleet_config_watch.retarget(&config).await;
+                    FleetAddonConfig::reconcile_config_sync(config, ctx).await
+                }
+            },
+            error_policy,
+            state.to_context(client.clone()),
+        )
+        .default_backoff()
+        .for_each(|_| futures::future::ready(()));
 
     let dynamic_watches_controller = Controller::new(
         Api::<FleetAddonConfig>::all(client.clone()),
@@ -172,14 +249,10 @@ pub async fn run_fleet_addon_config_controller(state: State) {
         .for_each(|_| futures::future::ready(()));
 
     // Reconcile initial state of watches
-    Arc::new(
-        fetch_config(client.clone())
-            .await
-            .expect("failed to get FleetAddonConfig resource"),
-    )
-    .update_watches(state.to_context(client.clone()))
-    .await
-    .expect("Initial dynamic watches setup to succeed");
+    config
+        .update_watches(state.to_context(client.clone()))
+        .await
+        .expect("Initial dynamic watches setup to succeed");
 
     tokio::select! {
         _ = watcher => {panic!("This should not happen before controllers exit")},
@@ -199,7 +272,28 @@ pub async fn run_fleet_helm_controller(state: State) {
     .default_with_reflect(writer)
     .predicate_filter(predicates::generation);
 
+    // Changes in referenced chart values trigger chart upgrade, only labeled sources are watched
+    let values_config_maps = metadata_watcher(
+        Api::<ConfigMap>::all(client.clone()),
+        Config::default().labels(CHART_VALUES_LABEL).any_semantic(),
+    )
+    .default_handling();
+
+    let values_secrets = metadata_watcher(
+        Api::<Secret>::all(client.clone()),
+        Config::default().labels(CHART_VALUES_LABEL).any_semantic(),
+    )
+    .default_handling();
+
+    let config_maps_reader = reader.clone();
+    let secrets_reader = reader.clone();
     let fleet_addon_config_controller = Controller::for_stream(fleet_addon_config, reader)
+        .watches_stream(values_config_maps, move |config_map| {
+            values_references(&config_maps_reader, "ConfigMap", config_map)
+        })
+        .watches_stream(values_secrets, move |secret| {
+            values_references(&secrets_reader, "Secret", secret)
+        })
         .shutdown_on_signal()
         .run(
             |obj, ctx| async move {
@@ -251,6 +345,8 @@ pub async fn run_fleet_helm_controller(state: State) {
                 match res {
                     Ok(_) => match patch {
                         Ok(_) => res,
+                        // Config is removed once the uninstall finalizer is released
+                        Err(kube::Error::Api(e)) if e.code == 404 => res,
                         Err(e) => Ok(Err(e)?),
                     },
                     e => e,
@@ -264,12 +360,132 @@ pub async fn run_fleet_helm_controller(state: State) {
     tokio::join!(fleet_addon_config_controller);
 }
 
+type ConfigMapWatcher = Pin<
+    Box<
+        dyn Stream<Item = Result<watcher::Event<PartialObjectMeta<ConfigMap>>, watcher::Error>>
+            + Send,
+    >,
+>;
+
+/// FleetConfigWatch tracks the fleet controller ConfigMap referenced by the FleetAddonConfig.
+#[derive(Clone)]
+struct FleetConfigWatch {
+    client: Client,
+    target: Arc<Mutex<(String, String)>>,
+    stream: BroadcastStream<SelectAll<ConfigMapWatcher>>,
+}
+
+impl FleetConfigWatch {
+    fn new(client: Client, config: &FleetAddonConfig) -> Self {
+        let target = (
+            config.spec.fleet_namespace(),
+            config.spec.fleet_config_name(),
+        );
+        let mut stream = SelectAll::new();
+        stream.push(Self::watcher(client.clone(), &target));
+        Self {
+            client,
+            target: Arc::new(Mutex::new(target)),
+            stream: BroadcastStream::new(Arc::new(Mutex::new(stream))),
+        }
+    }
+
+    /// Metadata watch scoped to the ConfigMap namespace and name.
+    fn watcher(client: Client, (namespace, name): &(String, String)) -> ConfigMapWatcher {
+        metadata_watcher(
+            Api::<ConfigMap>::namespaced(client, namespace),
+            Config::default()
+                .fields(&format!("metadata.name={name}"))
+                .any_semantic(),
+        )
+        .boxed()
+    }
+
+    /// Replace the watch once the FleetAddonConfig references a different ConfigMap.
+    async fn retarget(&self, config: &FleetAddonConfig) {
+        let target = (
+            config.spec.fleet_namespace(),
+            config.spec.fleet_config_name(),
+        );
+        let mut current = self.target.lock().await;
+        if *current == target {
+            return;
+        }
+
+        let mut stream = self.stream.stream.lock().await;
+        stream.clear();
+        stream.push(Self::watcher(self.client.clone(), &target));
+
+        let (namespace, name) = &target;
+        info!("Reconciled fleet config watch: watching ConfigMap {namespace}/{name}");
+        *current = target;
+    }
+}
+
+/// Reference to the CAPI Cluster set in the resource cluster name label.
+fn cluster_reference(obj: impl Resource) -> Option<ObjectRef<Cluster>> {
+    let name = obj.labels().get(CLUSTER_NAME_LABEL)?;
+    Some(ObjectRef::new(name).within(&obj.namespace()?))
+}
+
+/// Imported CAPI clusters in the namespace of the shared ClusterGroup.
+fn shared_group_references(
+    reader: &Store<Cluster>,
+    group: impl Resource,
+) -> Vec<ObjectRef<Cluster>> {
+    let namespace = group.namespace();
+    reader
+        .state()
+        .into_iter()
+        .filter(|cluster| cluster.namespace() == namespace)
+        .filter(|cluster| cluster.finalizers().iter().any(|f| f == FLEET_FINALIZER))
+        .map(|cluster| ObjectRef::from_obj(cluster.deref()))
+        .collect()
+}
+
+/// FleetAddonConfig resources using the ConfigMap as the fleet controller config.
+fn fleet_config_references(
+    reader: &Store<FleetAddonConfig>,
+    config_map: impl Resource,
+) -> Vec<ObjectRef<FleetAddonConfig>> {
+    let namespace = config_map.namespace().unwrap_or_default();
+    let name = config_map.name_any();
+    reader
+        .state()
+        .into_iter()
+        .filter(|config| {
+            config.spec.fleet_namespace() == namespace && config.spec.fleet_config_name() == name
+        })
+        .map(|config| ObjectRef::from_obj(config.deref()))
+        .collect()
+}
+
+fn values_references(
+    reader: &Store<FleetAddonConfig>,
+    kind: &str,
+    obj: impl Resource,
+) -> Vec<ObjectRef<FleetAddonConfig>> {
+    let namespace = obj.namespace().unwrap_or_default();
+    let name = obj.name_any();
+    reader
+        .state()
+        .into_iter()
+        .filter(|config| config.spec.values_reference(kind, &namespace, &name))
+        .map(|config| ObjectRef::from_obj(config.deref()))
+        .collect()
+}
+
 /// Initialize the controller and shared state (given the crd is installed)
-pub async fn run_cluster_controller(state: State) {
+pub async fn run_cluster_controller(mut state: State) {
     let client = Client::try_default()
         .await
         .expect("failed to create kube Client");
 
+    // Imported clusters are re-evaluated only when the de-import settings change
+    let configs = state
+        .config_watch(client.clone())
+        .predicate_filter(deimport_settings);
+
     let (sub, reader) = state.dispatcher.subscribe();
     let ns_controller = Controller::for_shared_stream(sub, reader)
         .shutdown_on_signal()
@@ -295,23 +511,64 @@ pub async fn run_cluster_controller(state: State) {
     )
     .default_handling();
 
+    let [infrastructure_groups, control_plane_groups, version_groups] = [
+        INFRASTRUCTURE_KIND_LABEL,
+        CONTROL_PLANE_KIND_LABEL,
+        KUBERNETES_MINOR_VERSION_LABEL,
+    ]
+    .map(|label| {
+        metadata_watcher(
+            Api::<ClusterGroup>::all(client.clone()),
+            Config::default().labels(label).any_semantic(),
+        )
+        .default_handling()
+    });
+
     let mappings = metadata_watcher(
         Api::<BundleNamespaceMapping>::all(client.clone()),
         Config::default().any_semantic(),
     )
     .default_handling();
 
+    // Kubeconfig secrets and machines are tracked by the cluster readiness gate,
+    // kubeconfig rotation triggers the Fleet agent redeployment
+    let kubeconfig_secrets = metadata_watcher(
+        Api::<Secret>::all(client.clone()),
+        Config::default().labels(CLUSTER_NAME_LABEL).any_semantic(),
+    )
+    .default_handling();
+
+    let machines = metadata_watcher(
+        Api::<Machine>::all(client.clone()),
+        Config::default().labels(CLUSTER_NAME_LABEL).any_semantic(),
+    )
+    .default_handling();
+
+    // Provider and version groups are shared by the clusters in the namespace, without owner references
     let (sub, reader) = state.dispatcher.subscribe();
+    let [infrastructure_reader, control_plane_reader, version_reader] =
+        [reader.clone(), reader.clone(), reader.clone()];
     let clusters = Controller::for_shared_stream(sub, reader.clone())
         .owns_stream(fleet)
         .owns_stream(groups)
+        .watches_stream(infrastructure_groups, move |group| {
+            shared_group_references(&infrastructure_reader, group)
+        })
+        .watches_stream(control_plane_groups, move |group| {
+            shared_group_references(&control_plane_reader, group)
+        })
+        .watches_stream(version_groups, move |group| {
+            shared_group_references(&version_reader, group)
+        })
+        .watches_stream(kubeconfig_secrets, cluster_reference)
+        .watches_stream(machines, cluster_reference)
         .watches_stream(mappings, move |mapping| {
             reader
                 .state()
                 .into_iter()
                 .filter_map(move |c: Arc<Cluster>| {
                     let in_namespace =
-                        c.spec.topology.as_ref()?.class_namespace == mapping.namespace();
+                        c.cluster_class_namespace().map(Into::into) == mapping.namespace();
                     in_namespace.then_some(ObjectRef::from_obj(c.deref()))
                 })
         })
@@ -324,15 +581,88 @@ pub async fn run_cluster_controller(state: State) {
         .default_backoff()
         .for_each(|_| futures::future::ready(()));
 
-    tokio::join!(clusters, ns_controller);
+    let bundle_deployments = metadata_watcher(
+        Api::<BundleDeployment>::all(client.clone()),
+        Config::default().labels(FLEET_CLUSTER_LABEL).any_semantic(),
+    )
+    .default_handling();
+
+    // Fleet clusters imported before the cluster name label was introduced are matched by name
+    let addons = Controller::new(
+        Api::<fleet_cluster::Cluster>::all(client.clone()),
+        Config::default().any_semantic(),
+    )
+    .watches_stream(bundle_deployments, |deployment| {
+        let labels = deployment.labels();
+        let name = labels.get(FLEET_CLUSTER_LABEL)?;
+        let namespace = labels.get(FLEET_CLUSTER_NAMESPACE_LABEL)?;
+        Some(ObjectRef::new(name).within(namespace))
+    })
+    .shutdown_on_signal()
+    .run(
+        fleet_cluster::Cluster::reconcile_addons,
+        error_policy,
+        state.to_context(client.clone()),
+    )
+    .default_backoff()
+    .for_each(|_| futures::future::ready(()));
+
+    // Previously imported clusters are tracked regardless of the import selectors, to de-import
+    // them once they leave the import scope
+    let (imported, writer) = reflector::store();
+    let imported_clusters = metadata_watcher(
+        Api::<Cluster>::all(client.clone()),
+        Config::default().any_semantic(),
+    )
+    .default_with_reflect(writer);
+
+    let namespaces = metadata_watcher(
+        Api::<Namespace>::all(client.clone()),
+        Config::default().any_semantic(),
+    )
+    .default_handling();
+
+    let ns_reader = imported.clone();
+    let config_reader = imported.clone();
+    let deimport = Controller::for_stream(imported_clusters, imported)
+        .watches_stream(namespaces, move |ns| {
+            ns_reader
+                .state()
+                .into_iter()
+                .filter(move |c| c.namespace().as_deref() == Some(ns.name_any().as_str()))
+                .filter(|c| c.finalizers().iter().any(|f| f == FLEET_FINALIZER))
+                .map(|c| ObjectRef::from_obj(c.deref()))
+        })
+        .watches_stream(configs, move |_| {
+            config_reader
+                .state()
+                .into_iter()
+                .filter(|c| c.finalizers().iter().any(|f| f == FLEET_FINALIZER))
+                .map(|c| ObjectRef::from_obj(c.deref()))
+        })
+        .shutdown_on_signal()
+        .run(
+            Cluster::deimport,
+            error_policy,
+            state.to_context(client.clone()),
+        )
+        .default_backoff()
+        .for_each(|_| futures::future::ready(()));
+
+    tokio::join!(clusters, ns_controller, addons, deimport);
 }
 
 /// Initialize the controller and shared state (given the crd is installed)
-pub async fn run_cluster_class_controller(state: State) {
+pub async fn run_cluster_class_controller(mut state: State) {
     let client = Client::try_default()
         .await
         .expect("failed to create kube Client");
 
+    // ClusterClasses are re-evaluated when the FleetAddonConfig spec changes
+    let configs = state
+        .config_watch(client.clone())
+        .predicate_filter(predicates::generation);
+
     let group_controller = Controller::new(
         Api::<ClusterGroup>::all(client.clone()),
         Config::default()
@@ -363,8 +693,15 @@ pub async fn run_cluster_class_controller(state: State) {
     )
     .default_handling();
 
+    let config_reader = reader.clone();
     let cluster_class_controller = Controller::for_stream(cluster_classes, reader)
         .owns_stream(groups)
+        .watches_stream(configs, move |_| {
+            config_reader
+                .state()
+                .into_iter()
+                .map(|c| ObjectRef::from_obj(c.deref()))
+        })
         .shutdown_on_signal()
         .run(
             ClusterClass::reconcile,
@@ -382,3 +719,77 @@ fn error_policy(doc: Arc<impl kube::Resource>, error: &Error, ctx: Arc<Context>)
     ctx.metrics.reconcile_failure(doc, error);
     Action::requeue(Duration::from_secs(10))
 }
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube::api::ObjectMeta;
+    use kube::core::PartialObjectMeta;
+    use kube::runtime::reflector::{self, ObjectRef};
+    use kube::runtime::watcher;
+
+    use crate::api::fleet_addon_config::{FleetAddonConfig, FleetAddonConfigSpec};
+    use crate::fixtures::mock_context;
+
+    use super::{fleet_config_references, FleetConfigWatch};
+
+    #[test]
+    fn test_fleet_config_references() {
+        let (reader, mut writer) = reflector::store();
+        let config = FleetAddonConfig {
+            metadata: ObjectMeta {
+                name: Some("fleet-addon-config".into()),
+                ..Default::default()
+            },
+            spec: FleetAddonConfigSpec {
+                fleet_namespace: Some("fleet-system".into()),
+                fleet_config_name: Some("fleet-config".into()),
+                ..Default::default()
+            },
+            status: None,
+        };
+        writer.apply_watcher_event(&watcher::Event::Apply(config.clone()));
+
+        let config_map = |namespace: &str, name: &str| PartialObjectMeta::<ConfigMap> {
+            metadata: ObjectMeta {
+                name: Some(name.into()),
+                namespace: Some(namespace.into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            fleet_config_references(&reader, config_map("fleet-system", "fleet-config")),
+            vec![ObjectRef::from_obj(&config)]
+        );
+        assert!(fleet_config_references(&reader, config_map("fleet-system", "other")).is_empty());
+        assert!(fleet_config_references(
+            &reader,
+            config_map("cattle-fleet-system", "fleet-config")
+        )
+        .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fleet_config_watch_retarget() {
+        let (ctx, _) = mock_context(|_| None);
+        let mut config = FleetAddonConfig::default();
+        let watch = FleetConfigWatch::new(ctx.client.clone(), &config);
+        let target = || async { watch.target.lock().await.clone() };
+        assert_eq!(
+            target().await,
+            ("cattle-fleet-system".into(), "fleet-controller".into())
+        );
+
+        config.spec.fleet_namespace = Some("fleet-system".into());
+        watch.retarget(&config).await;
+        assert_eq!(
+            target().await,
+            ("fleet-system".into(), "fleet-controller".into())
+        );
+
+        // Previous watch is replaced
+        assert_eq!(watch.stream.stream.lock().await.len(), 1);
+    }
+}
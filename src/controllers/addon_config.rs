@@ -1,11 +1,14 @@
 use base64::prelude::*;
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use cluster_api_rs::capi_cluster::Cluster;
 use futures::StreamExt as _;
-use std::{fmt::Display, io, str::FromStr, sync::Arc, time::Duration};
+use std::{collections::BTreeSet, env, fmt::Display, io, str::FromStr, sync::Arc, time::Duration};
 
 use k8s_openapi::{
-    api::core::v1::{self, ConfigMap, Endpoints, ObjectReference},
+    api::{
+        apps::v1::Deployment,
+        core::v1::{self, ConfigMap, Endpoints, ObjectReference, Secret},
+    },
     apimachinery::pkg::apis::meta::v1::{Condition, Time},
 };
 use kube::{
@@ -14,19 +17,27 @@ use kube::{
     core::object::HasSpec,
     runtime::{
         controller::Action,
-        watcher::{self, Config, Event},
+        events::{Event, EventType},
+        watcher::{self, Config},
     },
     Api, Resource, ResourceExt,
 };
+use semver::VersionReq;
 use serde::{de::DeserializeOwned, ser, Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use serde_with::{serde_as, DisplayFromStr};
 use thiserror::Error;
+use tokio::process::Child;
 use tracing::{field::display, info, instrument, Span};
 
 use crate::{
-    api::fleet_addon_config::{
-        FeatureGates, FleetAddonConfig, FleetSettings, Install, InstallOptions, Server,
+    api::{
+        capi_cluster::CLUSTER_NAME_LABEL,
+        fleet_addon_config::{
+            merge_values, ChartValues, FeatureGates, FleetAddonConfig, FleetSettings, Install,
+            InstallOptions, Server,
+        },
+        fleet_cluster,
     },
     telemetry,
 };
@@ -35,11 +46,21 @@ use super::{
     controller::{patch, Context},
     helm::{
         self,
-        install::{ChartSearch, FleetChart, HelmOperation},
+        install::{
+            ChartInfo, ChartSearch, FleetChart, HelmOperation, HelmRepository,
+            RepositoryCredentials, TempFile,
+        },
     },
     PatchError,
 };
 
+/// Finalizer set on the FleetAddonConfig to uninstall Fleet on deletion.
+pub static FLEET_UNINSTALL_FINALIZER: &str = "uninstall.fleet.addons.cluster.x-k8s.io";
+
+/// Name of the fleet-controller Deployment installed by the fleet chart.
+static FLEET_CONTROLLER_DEPLOYMENT: &str = "fleet-controller";
+const ROLLOUT_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
 #[resource(inherit = ConfigMap)]
 pub struct FleetConfig {
@@ -99,38 +120,47 @@ impl FleetAddonConfig {
     #[instrument(skip_all, fields(reconcile_id, name = self.name_any(), namespace = self.namespace()))]
     pub async fn reconcile_helm(&mut self, ctx: Arc<Context>) -> crate::Result<Action> {
         let _current = Span::current().record("reconcile_id", display(telemetry::get_trace_id()));
+        if self.metadata.deletion_timestamp.is_some() {
+            return Ok(self.uninstall(ctx).await?);
+        }
+
+        self.sync_finalizer(ctx.clone()).await?;
         if let Some(requeue) = self.update_flags(ctx.clone()).await? {
             return Ok(requeue);
         }
 
         let chart = FleetChart {
-            repo: "https://rancher.github.io/fleet-helm-charts/".into(),
-            namespace: "cattle-fleet-system".into(),
+            repo: self.helm_repository(ctx.clone()).await?,
+            namespace: self.spec.fleet_namespace(),
             wait: true,
             update_dependency: true,
             create_namespace: true,
             bootstrap_local_cluster: false,
             feature_gates: self.spec.feature_gates().cloned().unwrap_or_default(),
-            version: Default::default(),
+            ..Default::default()
         };
 
         let status = self.status.get_or_insert_default();
-        chart.add_repo()?.wait().await?;
+        if let Some(add) = chart.add_repo().await? {
+            Self::check_exit("helm repo add", add).await?;
+        }
 
         status.conditions.push(Condition {
             last_transition_time: Time(Local::now().to_utc()),
-            message: format!("Repo added: {}", chart.repo),
+            message: format!("Repo added: {}", chart.repo.url),
             observed_generation: self.metadata.generation,
             reason: "RepoAdd".into(),
             status: "True".into(),
             type_: "RepoAdd".into(),
         });
 
-        chart.update_repo()?.wait().await?;
+        if let Some(update) = chart.update_repo()? {
+            Self::check_exit("helm repo update", update).await?;
+        }
 
         status.conditions.push(Condition {
             last_transition_time: Time(Local::now().to_utc()),
-            message: format!("Repo updated: {}", chart.repo),
+            message: format!("Repo updated: {}", chart.repo.url),
             observed_generation: self.metadata.generation,
             reason: "RepoUpdate".into(),
             status: "True".into(),
@@ -139,10 +169,18 @@ impl FleetAddonConfig {
 
         if let Some(install) = &self.spec.install {
             if let Some(requeue) = self
-                .install_fleet(FleetChart {
-                    version: Some(install.install_version.clone()),
-                    ..chart.clone()
-                })
+                .install_fleet(
+                    ctx.clone(),
+                    FleetChart {
+                        version: Some(install.install_version.clone()),
+                        values: self.chart_values(ctx.clone(), &install.values).await?,
+                        crd_values: match install.crd.as_ref() {
+                            Some(values) => self.chart_values(ctx.clone(), values).await?,
+                            None => None,
+                        },
+                        ..chart.clone()
+                    },
+                )
                 .await?
             {
                 return Ok(requeue);
@@ -158,8 +196,10 @@ impl FleetAddonConfig {
         ctx: Arc<Context>,
     ) -> crate::Result<Action> {
         let _current = Span::current().record("reconcile_id", display(telemetry::get_trace_id()));
-        let ns = Namespace::from("cattle-fleet-system");
-        let mut fleet_config: FleetConfig = ctx.client.get("fleet-controller", &ns).await?;
+        let namespace = self.spec.fleet_namespace();
+        let ns = Namespace::from(namespace.clone());
+        let mut fleet_config: FleetConfig =
+            ctx.client.get(&self.spec.fleet_config_name(), &ns).await?;
 
         if let Some(server) = self.spec().config.as_ref().and_then(|c| c.server.as_ref()) {
             self.update_certificate(ctx.clone(), &mut fleet_config, server)
@@ -171,7 +211,7 @@ impl FleetAddonConfig {
         fleet_config.meta_mut().managed_fields = None;
         fleet_config.types = Some(TypeMeta::resource::<FleetConfig>());
 
-        let api: Api<FleetConfig> = Api::namespaced(ctx.client.clone(), "cattle-fleet-system");
+        let api: Api<FleetConfig> = Api::namespaced(ctx.client.clone(), &namespace);
         api.patch(
             &fleet_config.name_any(),
             &PatchParams::apply("addon-provider-fleet").force(),
@@ -190,48 +230,63 @@ impl FleetAddonConfig {
         ctx: Arc<Context>,
     ) -> DynamicWatcherResult<Action> {
         info!("Reconciling dynamic watches");
-        let cluster_selector = self.cluster_selector()?;
-        let ns_selector = self.namespace_selector()?;
-        let ns_config = Config::default().labels_from(&ns_selector);
-        let cluster_config = Config::default().labels_from(&cluster_selector);
+        // Identical selectors are watched once, covering the union of all import profile selectors
+        let cluster_selectors: BTreeSet<String> = self
+            .cluster_selectors()?
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let ns_selectors: BTreeSet<String> = self
+            .namespace_selectors()?
+            .iter()
+            .map(ToString::to_string)
+            .collect();
 
         let mut stream = ctx.stream.stream.lock().await;
         stream.clear();
 
-        if ctx.version >= 32 {
-            stream.push(
-                watcher::watcher(
-                    Api::all_with(ctx.client.clone(), &ApiResource::erase::<Cluster>(&())),
-                    cluster_config.streaming_lists(),
-                )
-                .boxed(),
-            );
-
-            stream.push(
-                watcher::watcher(
-                    Api::all_with(
-                        ctx.client.clone(),
-                        &ApiResource::erase::<v1::Namespace>(&()),
-                    ),
-                    ns_config.streaming_lists(),
-                )
-                .boxed(),
-            );
-        } else {
-            stream.push(
-                watcher::watcher(Api::<Cluster>::all(ctx.client.clone()), cluster_config)
-                    .map(to_dynamic_event)
+        for cluster_selector in &cluster_selectors {
+            let cluster_config = Config::default().labels(cluster_selector);
+            match ctx.version >= 32 {
+                true => stream.push(
+                    watcher::watcher(
+                        Api::all_with(ctx.client.clone(), &ApiResource::erase::<Cluster>(&())),
+                        cluster_config.streaming_lists(),
+                    )
                     .boxed(),
-            );
+                ),
+                false => stream.push(
+                    watcher::watcher(Api::<Cluster>::all(ctx.client.clone()), cluster_config)
+                        .map(to_dynamic_event)
+                        .boxed(),
+                ),
+            }
+        }
 
-            stream.push(
-                watcher::watcher(Api::<v1::Namespace>::all(ctx.client.clone()), ns_config)
-                    .map(to_dynamic_event)
+        for ns_selector in &ns_selectors {
+            let ns_config = Config::default().labels(ns_selector);
+            match ctx.version >= 32 {
+                true => stream.push(
+                    watcher::watcher(
+                        Api::all_with(
+                            ctx.client.clone(),
+                            &ApiResource::erase::<v1::Namespace>(&()),
+                        ),
+                        ns_config.streaming_lists(),
+                    )
                     .boxed(),
-            );
+                ),
+                false => stream.push(
+                    watcher::watcher(Api::<v1::Namespace>::all(ctx.client.clone()), ns_config)
+                        .map(to_dynamic_event)
+                        .boxed(),
+                ),
+            }
         }
 
-        info!("Reconciled dynamic watches to match selectors: namespace={ns_selector}, cluster={cluster_selector}");
+        let cluster_selectors = Vec::from_iter(cluster_selectors).join("; ");
+        let ns_selectors = Vec::from_iter(ns_selectors).join("; ");
+        info!("Reconciled dynamic watches to match selectors: namespace=[{ns_selectors}], cluster=[{cluster_selectors}]");
         Ok(Action::await_change())
     }
 
@@ -246,6 +301,79 @@ impl FleetAddonConfig {
         Ok(Action::await_change())
     }
 
+    /// Resolve and merge user supplied chart values from inline values and referenced sources.
+    async fn chart_values(
+        &self,
+        ctx: Arc<Context>,
+        values: &ChartValues,
+    ) -> ChartValuesResult<Option<Value>> {
+        if values.is_empty() {
+            return Ok(None);
+        }
+
+        let mut merged = Value::Object(Default::default());
+        for source in values.values_from.iter().flatten() {
+            let key = source.values_key();
+            let data = match source.is_secret() {
+                true => {
+                    let secret: Secret = ctx.client.fetch(&source.reference).await?;
+                    secret
+                        .data
+                        .unwrap_or_default()
+                        .get(key)
+                        .map(|data| String::from_utf8_lossy(&data.0).to_string())
+                }
+                false => {
+                    let config_map: ConfigMap = ctx.client.fetch(&source.reference).await?;
+                    config_map.data.unwrap_or_default().get(key).cloned()
+                }
+            };
+
+            let name = source.reference.name.clone().unwrap_or_default();
+            let data = data.ok_or(ChartValuesError::MissingKey(key.to_string(), name))?;
+            merge_values(&mut merged, serde_yaml::from_str(&data)?);
+        }
+
+        if let Some(inline) = values.values.clone() {
+            merge_values(&mut merged, inline);
+        }
+
+        Ok(Some(merged))
+    }
+
+    /// Resolve chart repository settings, including credentials and CA bundle from the referenced secret.
+    async fn helm_repository(&self, ctx: Arc<Context>) -> helm::RepoAuthResult<HelmRepository> {
+        let repository = self.spec.chart_repository();
+        let mut repo = HelmRepository {
+            name: repository.repository_name(),
+            url: repository.url.clone(),
+            insecure_skip_tls_verify: repository.insecure_skip_tls_verify.unwrap_or_default(),
+            ..Default::default()
+        };
+
+        let Some(secret_ref) = repository.secret_ref.as_ref() else {
+            return Ok(repo);
+        };
+
+        let secret: Secret = ctx.client.fetch(secret_ref).await?;
+        let data = secret.data.unwrap_or_default();
+        let value = |key: &str| {
+            data.get(key)
+                .map(|value| String::from_utf8_lossy(&value.0).to_string())
+        };
+
+        if let (Some(username), Some(password)) = (value("username"), value("password")) {
+            repo.credentials = Some(RepositoryCredentials { username, password });
+        }
+
+        if let Some(ca) = value("ca.crt") {
+            let ca_file = env::temp_dir().join(format!("{}-ca.crt", repo.name));
+            repo.ca_file = Some(Arc::new(TempFile::write(ca_file, ca)?));
+        }
+
+        Ok(repo)
+    }
+
     fn default_endpoint_lookup(&self, endpoints: Endpoints) -> Option<String> {
         let subsets = endpoints.subsets?;
         let subnet = subsets.first()?.clone();
@@ -314,32 +442,71 @@ impl FleetAddonConfig {
         Ok(())
     }
 
-    async fn install_fleet(&mut self, chart: FleetChart) -> AddonConfigSyncResult<Option<Action>> {
-        let expected_version = match chart.version.as_ref() {
-            Some(version) => &version.clone().normalized(),
+    async fn install_fleet(
+        &mut self,
+        ctx: Arc<Context>,
+        chart: FleetChart,
+    ) -> AddonConfigSyncResult<Option<Action>> {
+        if let Some(requeue) = self.verify_rollout(ctx.clone(), &chart).await? {
+            return Ok(Some(requeue));
+        }
+
+        let (chart, expected_version) = match chart.version.clone().map(Install::normalized) {
+            Some(Install::Constraint(constraint)) => {
+                let requirement = VersionReq::parse(&constraint)?;
+                let found = chart.search_versions("fleet").await?;
+                let Some(resolved) = ChartSearch::highest_matching(&requirement, found) else {
+                    return Err(AddonConfigSyncError::NoMatchingVersion(constraint));
+                };
+
+                info!(
+                    "Resolved fleet version {} for constraint {constraint}",
+                    resolved.version
+                );
+                let version = Install::Version(resolved.version);
+                (
+                    FleetChart {
+                        version: Some(version.clone()),
+                        ..chart
+                    },
+                    version.normalized(),
+                )
+            }
+            Some(version) => (chart, version),
             None => return Ok(None),
         };
+        let expected_version = &expected_version;
 
-        let status = self.status.get_or_insert_default();
+        let values_hash = chart.values_hash();
+        let previous_hash = &self.status.get_or_insert_default().values_hash;
+        let values_changed = *previous_hash != values_hash;
+        let chart = FleetChart {
+            reset_values: values_changed && previous_hash.is_some(),
+            ..chart
+        };
         let installed_chart_meta = FleetChart::get_metadata("fleet-crd").await?;
-        let search_result = chart
-            .search_repo()
-            .await?
-            .into_iter()
-            .find(|r| r.name == "fleet/fleet-crd");
+        let search_result = chart.search_chart("fleet-crd").await?;
         match (installed_chart_meta, search_result, expected_version) {
             (Some(installed), Some(search), Install::FollowLatest(true))
                 if search.app_version != installed.app_version =>
             {
-                chart.fleet_crds(&HelmOperation::Upgrade)?.wait().await?;
+                self.upgrade_crds(ctx.clone(), &chart, &installed).await?;
             }
             (Some(installed), Some(_), Install::Version(expected))
                 if expected.strip_prefix("v").unwrap_or(expected) != installed.app_version =>
             {
-                chart.fleet_crds(&HelmOperation::Upgrade)?.wait().await?;
+                self.upgrade_crds(ctx.clone(), &chart, &installed).await?;
             }
             (None, Some(_), _) => {
-                chart.fleet_crds(&HelmOperation::Install)?.wait().await?;
+                Self::check_exit(
+                    "fleet-crd install",
+                    chart.fleet_crds(&HelmOperation::Install).await?,
+                )
+                .await?;
+            }
+            (Some(installed), Some(_), _) if values_changed => {
+                let pinned = chart.pinned(installed.app_version.clone());
+                self.upgrade_crds(ctx.clone(), &pinned, &installed).await?;
             }
             (Some(_), Some(_), Install::FollowLatest(false)) => {}
             (Some(_), Some(_), Install::Version(_)) => {}
@@ -347,13 +514,9 @@ impl FleetAddonConfig {
         };
 
         let installed_chart_meta = FleetChart::get_metadata("fleet").await?;
-        let search_result = chart
-            .search_repo()
-            .await?
-            .into_iter()
-            .find(|r| r.name == "fleet/fleet");
+        let search_result = chart.search_chart("fleet").await?;
 
-        match (
+        let (message, version) = match (
             installed_chart_meta,
             search_result.as_ref(),
             expected_version,
@@ -361,52 +524,518 @@ impl FleetAddonConfig {
             (Some(installed), Some(search), Install::FollowLatest(true))
                 if search.app_version != installed.app_version =>
             {
-                chart.fleet(&HelmOperation::Upgrade)?.wait().await?;
-                status.conditions.push(Condition {
-                    last_transition_time: Time(Local::now().to_utc()),
-                    message: format!("Updated fleet to version {}", search.app_version),
-                    observed_generation: self.metadata.generation,
-                    reason: "Installed".into(),
-                    status: "True".into(),
-                    type_: "Installed".into(),
-                });
-                status.installed_version = search.app_version.clone().into();
+                if !self.upgrade_fleet(ctx, &chart, &installed).await? {
+                    return Ok(None);
+                }
+                (
+                    format!("Updated fleet to version {}", search.app_version),
+                    search.app_version.clone(),
+                )
             }
             (Some(installed), Some(_), Install::Version(expected))
                 if expected.strip_prefix("v").unwrap_or(expected) != installed.app_version =>
             {
-                chart.fleet(&HelmOperation::Upgrade)?.wait().await?;
-                status.conditions.push(Condition {
-                    last_transition_time: Time(Local::now().to_utc()),
-                    message: format!("Updated fleet to version {}", expected),
-                    observed_generation: self.metadata.generation,
-                    reason: "Installed".into(),
-                    status: "True".into(),
-                    type_: "Installed".into(),
-                });
-                status.installed_version = expected.clone().into();
+                if !self.upgrade_fleet(ctx, &chart, &installed).await? {
+                    return Ok(None);
+                }
+                (
+                    format!("Updated fleet to version {}", expected),
+                    expected.clone(),
+                )
             }
             (None, Some(ChartSearch { app_version, .. }), Install::FollowLatest(_))
             | (None, Some(_), Install::Version(app_version)) => {
-                chart.fleet(&HelmOperation::Install)?.wait().await?;
-                status.conditions.push(Condition {
-                    last_transition_time: Time(Local::now().to_utc()),
-                    message: format!("Installed fleet version {}", app_version),
-                    observed_generation: self.metadata.generation,
-                    reason: "Installed".into(),
-                    status: "True".into(),
-                    type_: "Installed".into(),
-                });
-                status.installed_version = app_version.clone().into();
+                Self::check_exit("fleet install", chart.fleet(&HelmOperation::Install).await?)
+                    .await?;
+                (
+                    format!("Installed fleet version {}", app_version),
+                    app_version.clone(),
+                )
+            }
+            (Some(installed), Some(_), _) if values_changed => {
+                let pinned = chart.pinned(installed.app_version.clone());
+                if !self.upgrade_fleet(ctx, &pinned, &installed).await? {
+                    return Ok(None);
+                }
+                (
+                    format!("Updated fleet {} chart values", installed.app_version),
+                    installed.app_version,
+                )
             }
             (Some(installed), Some(_), Install::FollowLatest(false)) => {
+                let status = self.status.get_or_insert_default();
                 status.installed_version = installed.app_version.into();
+                status.values_hash = values_hash;
+                return Ok(None);
+            }
+            (Some(_), Some(_), Install::Version(_)) => {
+                self.status.get_or_insert_default().values_hash = values_hash;
+                return Ok(None);
             }
-            (Some(_), Some(_), Install::Version(_)) => {}
             (_, _, _) => return Ok(Some(Action::requeue(Duration::from_secs(10)))),
         };
 
-        Ok(None)
+        let status = self.status.get_or_insert_default();
+        status.conditions.push(Condition {
+            last_transition_time: Time(Local::now().to_utc()),
+            message,
+            observed_generation: self.metadata.generation,
+            reason: "Installed".into(),
+            status: "True".into(),
+            type_: "Installed".into(),
+        });
+        status.installed_version = version.into();
+        status.values_hash = values_hash;
+
+        // Upgraded controller rollout is verified on requeue
+        Ok(self
+            .rollout_started()
+            .map(|_| Action::requeue(ROLLOUT_CHECK_INTERVAL)))
+    }
+
+    /// Upgrade the fleet release. Failed upgrades are rolled back to the previously installed revision,
+    /// successful ones wait for the fleet-controller rollout verification.
+    ///
+    /// Returns `true` if the upgrade was applied.
+    async fn upgrade_fleet(
+        &mut self,
+        ctx: Arc<Context>,
+        chart: &FleetChart,
+        installed: &ChartInfo,
+    ) -> AddonConfigSyncResult<bool> {
+        self.status.get_or_insert_default().previous_revision = Some(installed.revision.clone());
+
+        let result = chart.fleet(&HelmOperation::Upgrade).await?.wait().await?;
+        if result.success() {
+            self.upgrade_condition(
+                "Unknown",
+                "RolloutInProgress",
+                "Waiting for fleet controller availability after upgrade".into(),
+            );
+            return Ok(true);
+        }
+
+        let failure = format!("helm upgrade exited with {result}");
+        self.rollback(ctx, chart, "fleet", Some(&installed.revision), failure)
+            .await?;
+        Ok(false)
+    }
+
+    /// Upgrade the fleet-crd release, rolling it back to the previously installed revision on failure.
+    async fn upgrade_crds(
+        &mut self,
+        ctx: Arc<Context>,
+        chart: &FleetChart,
+        installed: &ChartInfo,
+    ) -> AddonConfigSyncResult<()> {
+        let result = chart
+            .fleet_crds(&HelmOperation::Upgrade)
+            .await?
+            .wait()
+            .await?;
+        if result.success() {
+            return Ok(());
+        }
+
+        let failure = format!("helm upgrade fleet-crd exited with {result}");
+        self.rollback(ctx, chart, "fleet-crd", Some(&installed.revision), failure)
+            .await?;
+        Err(AddonConfigSyncError::CommandFailed(
+            "fleet-crd upgrade".into(),
+            result.to_string(),
+        ))
+    }
+
+    /// Check the fleet-controller rollout after an upgrade. The release is rolled back to the previous
+    /// revision if the controller is not available within the rollout timeout.
+    ///
+    /// Returns the action to take while the rollout is verified or was rolled back.
+    async fn verify_rollout(
+        &mut self,
+        ctx: Arc<Context>,
+        chart: &FleetChart,
+    ) -> AddonConfigSyncResult<Option<Action>> {
+        let Some(started) = self.rollout_started() else {
+            return Ok(None);
+        };
+
+        let namespace = &chart.namespace;
+        let revision = self
+            .status
+            .as_ref()
+            .and_then(|status| status.previous_revision.clone());
+        let deployment = Api::<Deployment>::namespaced(ctx.client.clone(), namespace)
+            .get_opt(FLEET_CONTROLLER_DEPLOYMENT)
+            .await
+            .map_err(AddonConfigSyncError::HealthCheck)?;
+        let failure = match deployment {
+            Some(deployment) if Self::rollout_complete(&deployment) => {
+                self.upgrade_condition(
+                    "True",
+                    "RolloutComplete",
+                    "Fleet controller is available after upgrade".into(),
+                );
+                self.publish(
+                    ctx,
+                    EventType::Normal,
+                    "Upgraded",
+                    match &revision {
+                        Some(revision) => format!("Upgraded fleet from revision {revision}"),
+                        None => "Upgraded fleet".into(),
+                    },
+                )
+                .await?;
+                return Ok(None);
+            }
+            // Deployment may not be created yet right after the upgrade
+            _ if (Utc::now() - started).num_seconds() < self.rollout_timeout_seconds() => {
+                return Ok(Some(Action::requeue(ROLLOUT_CHECK_INTERVAL)));
+            }
+            Some(_) => format!(
+                "Deployment {namespace}/{FLEET_CONTROLLER_DEPLOYMENT} is not available after upgrade"
+            ),
+            None => format!("Deployment {namespace}/{FLEET_CONTROLLER_DEPLOYMENT} not found"),
+        };
+
+        self.rollback(ctx, chart, "fleet", revision.as_deref(), failure)
+            .await?;
+        if let Some(installed) = FleetChart::get_metadata("fleet").await? {
+            self.status.get_or_insert_default().installed_version = installed.app_version.into();
+        }
+
+        // Failed upgrade is retried on the next change
+        Ok(Some(Action::await_change()))
+    }
+
+    /// Start of the pending fleet-controller rollout verification, if any.
+    fn rollout_started(&self) -> Option<DateTime<Utc>> {
+        let condition = self
+            .status
+            .as_ref()?
+            .conditions
+            .iter()
+            .rfind(|c| c.type_ == "UpgradeHealthy")?;
+        (condition.reason == "RolloutInProgress").then_some(condition.last_transition_time.0)
+    }
+
+    fn upgrade_condition(&mut self, status: &str, reason: &str, message: String) {
+        let generation = self.metadata.generation;
+        self.status
+            .get_or_insert_default()
+            .conditions
+            .push(Condition {
+                last_transition_time: Time(Local::now().to_utc()),
+                message,
+                observed_generation: generation,
+                reason: reason.into(),
+                status: status.into(),
+                type_: "UpgradeHealthy".into(),
+            });
+    }
+
+    /// Report the failed upgrade and roll the release back to the given revision.
+    /// Without a recorded revision the rollback is skipped, and only the failure is reported.
+    async fn rollback(
+        &mut self,
+        ctx: Arc<Context>,
+        chart: &FleetChart,
+        release: &str,
+        revision: Option<&str>,
+        failure: String,
+    ) -> AddonConfigSyncResult<()> {
+        let generation = self.metadata.generation;
+        self.publish(
+            ctx.clone(),
+            EventType::Warning,
+            "UpgradeFailed",
+            failure.clone(),
+        )
+        .await?;
+        self.upgrade_condition("False", "UpgradeFailed", failure);
+
+        let Some(revision) = revision else {
+            let message =
+                format!("No previous revision of {release} is recorded, skipping rollback");
+            self.status
+                .get_or_insert_default()
+                .conditions
+                .push(Condition {
+                    last_transition_time: Time(Local::now().to_utc()),
+                    message: message.clone(),
+                    observed_generation: generation,
+                    reason: "NoPreviousRevision".into(),
+                    status: "False".into(),
+                    type_: "RolledBack".into(),
+                });
+            return self
+                .publish(ctx, EventType::Warning, "RolledBack", message)
+                .await;
+        };
+
+        let rollback = chart.rollback(release, revision)?.wait().await?;
+        let (rolled_back, rollback_message) = match rollback.success() {
+            true => (
+                "True",
+                format!("Rolled back {release} to revision {revision}"),
+            ),
+            false => (
+                "False",
+                format!("Rollback of {release} to revision {revision} exited with {rollback}"),
+            ),
+        };
+
+        let status = self.status.get_or_insert_default();
+        status.conditions.push(Condition {
+            last_transition_time: Time(Local::now().to_utc()),
+            message: rollback_message.clone(),
+            observed_generation: generation,
+            reason: match rollback.success() {
+                true => "RollbackSucceeded",
+                false => "RollbackFailed",
+            }
+            .into(),
+            status: rolled_back.into(),
+            type_: "RolledBack".into(),
+        });
+
+        let event_type = match rollback.success() {
+            true => EventType::Normal,
+            false => EventType::Warning,
+        };
+        self.publish(ctx, event_type, "RolledBack", rollback_message)
+            .await
+    }
+
+    /// Check if the config is being deleted, with Fleet pending the uninstall.
+    pub(crate) fn uninstalling(&self) -> bool {
+        self.metadata.deletion_timestamp.is_some()
+            && self
+                .finalizers()
+                .iter()
+                .any(|f| f == FLEET_UNINSTALL_FINALIZER)
+    }
+
+    /// Add or remove the uninstall finalizer, following the `uninstallOnDelete` setting.
+    async fn sync_finalizer(&mut self, ctx: Arc<Context>) -> UninstallResult<()> {
+        let present = self
+            .finalizers()
+            .iter()
+            .any(|f| f == FLEET_UNINSTALL_FINALIZER);
+        match (self.uninstall_on_delete(), present) {
+            (true, false) => self.finalizers_mut().push(FLEET_UNINSTALL_FINALIZER.into()),
+            (false, true) => self
+                .finalizers_mut()
+                .retain(|f| f != FLEET_UNINSTALL_FINALIZER),
+            _ => return Ok(()),
+        }
+
+        self.patch_finalizers(ctx).await
+    }
+
+    async fn patch_finalizers(&mut self, ctx: Arc<Context>) -> UninstallResult<()> {
+        let api: Api<Self> = Api::all(ctx.client.clone());
+        let patched = api
+            .patch(
+                &self.name_any(),
+                &Default::default(),
+                &Patch::Merge(json!({
+                    "metadata": {
+                        "resourceVersion": self.metadata.resource_version,
+                        "finalizers": self.finalizers(),
+                    },
+                })),
+            )
+            .await
+            .map_err(UninstallError::Finalizer)?;
+
+        // Keep status apply consistent with the updated resource version
+        self.metadata.resource_version = patched.metadata.resource_version;
+        Ok(())
+    }
+
+    /// Remove imported Fleet clusters, then uninstall `fleet` and `fleet-crd` releases.
+    /// The finalizer is released only after all steps are complete.
+    async fn uninstall(&mut self, ctx: Arc<Context>) -> UninstallResult<Action> {
+        if !self
+            .finalizers()
+            .iter()
+            .any(|f| f == FLEET_UNINSTALL_FINALIZER)
+        {
+            return Ok(Action::await_change());
+        }
+
+        let remaining = Self::remove_imported_clusters(ctx.clone()).await?;
+        if remaining > 0 {
+            self.uninstall_condition(
+                "ImportedClustersRemoved",
+                "Removing",
+                false,
+                format!("Waiting for {remaining} imported Fleet clusters to be removed"),
+            );
+            return Ok(Action::requeue(Duration::from_secs(5)));
+        }
+
+        self.uninstall_condition(
+            "ImportedClustersRemoved",
+            "Removed",
+            true,
+            "All imported Fleet clusters are removed".into(),
+        );
+
+        let chart = FleetChart {
+            namespace: self.spec.fleet_namespace(),
+            wait: true,
+            ..Default::default()
+        };
+        for (release, condition) in [
+            ("fleet", "FleetUninstalled"),
+            ("fleet-crd", "FleetCRDUninstalled"),
+        ] {
+            if FleetChart::get_metadata(release).await?.is_some() {
+                let result = chart.uninstall(release)?.wait().await?;
+                if !result.success() {
+                    let message = format!("helm uninstall {release} exited with {result}");
+                    self.uninstall_condition(condition, "UninstallFailed", false, message);
+                    return Err(UninstallError::CommandFailed(
+                        release.into(),
+                        result.to_string(),
+                    ));
+                }
+
+                info!("Uninstalled {release} release");
+            }
+
+            self.uninstall_condition(
+                condition,
+                "Uninstalled",
+                true,
+                format!("Release {release} is uninstalled"),
+            );
+        }
+
+        self.finalizers_mut()
+            .retain(|f| f != FLEET_UNINSTALL_FINALIZER);
+        self.patch_finalizers(ctx).await?;
+
+        Ok(Action::await_change())
+    }
+
+    /// Delete Fleet clusters imported for CAPI clusters, returning the number of clusters still present.
+    async fn remove_imported_clusters(ctx: Arc<Context>) -> UninstallResult<usize> {
+        let clusters = Api::<fleet_cluster::Cluster>::all(ctx.client.clone())
+            .list(&Default::default())
+            .await
+            .map_err(UninstallError::ImportedClusters)?;
+
+        let imported: Vec<_> = clusters
+            .items
+            .into_iter()
+            .filter(|cluster| {
+                cluster.labels().contains_key(CLUSTER_NAME_LABEL)
+                    || cluster.owner_references().iter().any(|owner| {
+                        owner.kind == "Cluster"
+                            && owner.api_version.starts_with("cluster.x-k8s.io/")
+                    })
+            })
+            .collect();
+
+        for cluster in imported
+            .iter()
+            .filter(|cluster| cluster.metadata.deletion_timestamp.is_none())
+        {
+            let api: Api<fleet_cluster::Cluster> =
+                Api::namespaced(ctx.client.clone(), &cluster.namespace().unwrap_or_default());
+            match api.delete(&cluster.name_any(), &Default::default()).await {
+                Ok(_) => info!("Deleted imported Fleet cluster {}", cluster.name_any()),
+                Err(kube::Error::Api(e)) if e.code == 404 => {}
+                Err(e) => return Err(UninstallError::ImportedClusters(e)),
+            }
+        }
+
+        Ok(imported.len())
+    }
+
+    fn uninstall_condition(&mut self, type_: &str, reason: &str, status: bool, message: String) {
+        let generation = self.metadata.generation;
+        self.status
+            .get_or_insert_default()
+            .conditions
+            .push(Condition {
+                last_transition_time: Time(Local::now().to_utc()),
+                message,
+                observed_generation: generation,
+                reason: reason.into(),
+                status: match status {
+                    true => "True",
+                    false => "False",
+                }
+                .into(),
+                type_: type_.into(),
+            });
+    }
+
+    /// Check the fleet-controller Deployment is available with all replicas updated.
+    fn rollout_complete(deployment: &Deployment) -> bool {
+        let Some(status) = deployment.status.as_ref() else {
+            return false;
+        };
+
+        let replicas = deployment
+            .spec
+            .as_ref()
+            .and_then(|s| s.replicas)
+            .unwrap_or(1);
+        let available = status
+            .conditions
+            .iter()
+            .flatten()
+            .any(|c| c.type_ == "Available" && c.status == "True");
+
+        available
+            && status.observed_generation >= deployment.metadata.generation
+            && status.updated_replicas.unwrap_or_default() >= replicas
+            && status.available_replicas.unwrap_or_default() >= replicas
+    }
+
+    async fn check_exit(command: &str, mut child: Child) -> AddonConfigSyncResult<()> {
+        let status = child.wait().await?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(AddonConfigSyncError::CommandFailed(
+                command.into(),
+                status.to_string(),
+            )),
+        }
+    }
+
+    async fn publish(
+        &self,
+        ctx: Arc<Context>,
+        type_: EventType,
+        reason: &str,
+        note: String,
+    ) -> AddonConfigSyncResult<()> {
+        match ctx
+            .diagnostics
+            .read()
+            .await
+            .recorder(ctx.client.clone())
+            .publish(
+                &Event {
+                    type_,
+                    reason: reason.into(),
+                    note: Some(note),
+                    action: "Upgrading".into(),
+                    secondary: None,
+                },
+                &self.object_ref(&()),
+            )
+            .await
+        {
+            // Ignore forbidden errors on event creation
+            Err(kube::Error::Api(e)) if &e.reason == "Forbidden" => Ok(()),
+            e => e.map_err(AddonConfigSyncError::Event),
+        }
     }
 
     async fn update_flags(&mut self, ctx: Arc<Context>) -> FleetPatchResult<Option<Action>> {
@@ -458,8 +1087,8 @@ impl FeatureGates {
 }
 
 pub fn to_dynamic_event<R>(
-    ev: Result<Event<R>, watcher::Error>,
-) -> Result<Event<DynamicObject>, watcher::Error>
+    ev: Result<watcher::Event<R>, watcher::Error>,
+) -> Result<watcher::Event<DynamicObject>, watcher::Error>
 where
     R: Resource<DynamicType = ()> + Serialize + DeserializeOwned,
 {
@@ -525,8 +1154,63 @@ pub enum AddonConfigSyncError {
     #[error("Fleet metadata check error: {0}")]
     MetadataGet(#[from] helm::MetadataGetError),
 
+    #[error("Fleet controller health check error: {0}")]
+    HealthCheck(#[source] kube::Error),
+
+    #[error("Event publish error: {0}")]
+    Event(#[source] kube::Error),
+
+    #[error("Invalid fleet version constraint: {0}")]
+    InvalidConstraint(#[from] semver::Error),
+
+    #[error("No fleet chart version matches constraint `{0}`")]
+    NoMatchingVersion(String),
+
+    #[error("Fleet rollback error: {0}")]
+    Rollback(#[from] helm::FleetRollbackError),
+
     #[error("Error waiting for command: {0}")]
     CommandError(#[from] io::Error),
+
+    #[error("Command `{0}` failed: {1}")]
+    CommandFailed(String, String),
+}
+
+pub type UninstallResult<T> = std::result::Result<T, UninstallError>;
+
+#[derive(Error, Debug)]
+pub enum UninstallError {
+    #[error("Imported Fleet clusters removal error: {0}")]
+    ImportedClusters(#[source] kube::Error),
+
+    #[error("Finalizer update error: {0}")]
+    Finalizer(#[source] kube::Error),
+
+    #[error("Fleet uninstall error: {0}")]
+    Uninstall(#[from] helm::FleetUninstallError),
+
+    #[error("Fleet metadata check error: {0}")]
+    MetadataGet(#[from] helm::MetadataGetError),
+
+    #[error("Error waiting for command: {0}")]
+    CommandError(#[from] io::Error),
+
+    #[error("Uninstall of `{0}` failed: {1}")]
+    CommandFailed(String, String),
+}
+
+pub type ChartValuesResult<T> = std::result::Result<T, ChartValuesError>;
+
+#[derive(Error, Debug)]
+pub enum ChartValuesError {
+    #[error("Values source fetch error: {0}")]
+    Fetch(#[from] kube::Error),
+
+    #[error("Values key `{0}` is missing in `{1}`")]
+    MissingKey(String, String),
+
+    #[error("Values parse error: {0}")]
+    Parse(#[from] serde_yaml::Error),
 }
 
 pub type DynamicWatcherResult<T> = std::result::Result<T, DynamicWatcherError>;
@@ -548,7 +1232,56 @@ pub enum ConfigMapSyncError {
     Patch(#[from] PatchError),
 }
 
+#[cfg(test)]
 mod tests {
+    use std::time::Duration as StdDuration;
+
+    use chrono::{Duration, Utc};
+    use http::Method;
+    use k8s_openapi::api::apps::v1::{
+        Deployment, DeploymentCondition, DeploymentSpec, DeploymentStatus,
+    };
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+    use kube::api::ObjectMeta;
+    use kube::runtime::controller::Action;
+    use serde_json::{json, Value};
+
+    use crate::api::fleet_addon_config::{
+        FleetAddonConfig, FleetAddonConfigSpec, FleetAddonConfigStatus, FleetInstall, Install,
+    };
+    use crate::fixtures::{list, mock_context};
+
+    use super::{FleetChart, FLEET_UNINSTALL_FINALIZER, ROLLOUT_CHECK_INTERVAL};
+
+    static CONFIG_PATH: &str =
+        "/apis/addons.cluster.x-k8s.io/v1alpha1/fleetaddonconfigs/fleet-addon-config";
+
+    fn fleet_cluster(name: &str, labels: Value, owners: Value) -> Value {
+        json!({
+            "apiVersion": "fleet.cattle.io/v1alpha1",
+            "kind": "Cluster",
+            "metadata": {
+                "name": name,
+                "namespace": "default",
+                "labels": labels,
+                "ownerReferences": owners,
+            },
+            "spec": {},
+        })
+    }
+
+    fn deleted_config(finalizers: Vec<String>) -> FleetAddonConfig {
+        FleetAddonConfig {
+            metadata: ObjectMeta {
+                name: Some("fleet-addon-config".into()),
+                deletion_timestamp: Some(Time(Utc::now())),
+                finalizers: Some(finalizers),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn test() {
         use crate::controllers::addon_config::FleetConfigData;
@@ -574,4 +1307,209 @@ mod tests {
 
         let _config: FleetConfigData = serde_json::from_str(data).unwrap();
     }
+
+    #[test]
+    fn test_rollout_complete() {
+        let mut deployment = Deployment {
+            metadata: ObjectMeta {
+                generation: Some(2),
+                ..Default::default()
+            },
+            spec: Some(DeploymentSpec {
+                replicas: Some(2),
+                ..Default::default()
+            }),
+            status: Some(DeploymentStatus {
+                observed_generation: Some(2),
+                updated_replicas: Some(2),
+                available_replicas: Some(2),
+                conditions: Some(vec![DeploymentCondition {
+                    type_: "Available".into(),
+                    status: "True".into(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            }),
+        };
+        assert!(FleetAddonConfig::rollout_complete(&deployment));
+
+        let status = deployment.status.as_mut().unwrap();
+        status.updated_replicas = Some(1);
+        assert!(!FleetAddonConfig::rollout_complete(&deployment));
+
+        let status = deployment.status.as_mut().unwrap();
+        status.updated_replicas = Some(2);
+        status.observed_generation = Some(1);
+        assert!(!FleetAddonConfig::rollout_complete(&deployment));
+
+        deployment.status = None;
+        assert!(!FleetAddonConfig::rollout_complete(&deployment));
+    }
+
+    #[test]
+    fn test_rollout_started() {
+        let started = Utc::now() - Duration::seconds(30);
+        let condition = |reason: &str, time| Condition {
+            type_: "UpgradeHealthy".into(),
+            reason: reason.into(),
+            last_transition_time: Time(time),
+            message: "".into(),
+            observed_generation: None,
+            status: "Unknown".into(),
+        };
+
+        let mut config = FleetAddonConfig::default();
+        assert!(config.rollout_started().is_none());
+
+        config.status = Some(FleetAddonConfigStatus {
+            conditions: vec![condition("RolloutInProgress", started)],
+            ..Default::default()
+        });
+        assert_eq!(config.rollout_started(), Some(started));
+
+        let status = config.status.as_mut().unwrap();
+        status
+            .conditions
+            .push(condition("RolloutComplete", Utc::now()));
+        assert!(config.rollout_started().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sync_finalizer() {
+        let (ctx, server) = mock_context(|request| {
+            (request.path == CONFIG_PATH).then(|| {
+                json!({
+                    "apiVersion": "addons.cluster.x-k8s.io/v1alpha1",
+                    "kind": "FleetAddonConfig",
+                    "metadata": {"name": "fleet-addon-config", "resourceVersion": "2"},
+                    "spec": {},
+                })
+            })
+        });
+
+        let mut config = FleetAddonConfig {
+            metadata: ObjectMeta {
+                name: Some("fleet-addon-config".into()),
+                ..Default::default()
+            },
+            spec: FleetAddonConfigSpec {
+                install: Some(FleetInstall {
+                    install_version: Install::FollowLatest(true),
+                    repository: None,
+                    values: Default::default(),
+                    crd: None,
+                    uninstall_on_delete: Some(true),
+                    rollout_timeout_seconds: None,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        config.sync_finalizer(ctx.clone()).await.unwrap();
+
+        let patches = server.requests(Method::PATCH, CONFIG_PATH);
+        assert_eq!(patches.len(), 1);
+        assert_eq!(
+            patches[0].body["metadata"]["finalizers"],
+            json!([FLEET_UNINSTALL_FINALIZER])
+        );
+        assert_eq!(config.metadata.resource_version, Some("2".into()));
+
+        // Finalizer already in place is not patched again
+        config.sync_finalizer(ctx).await.unwrap();
+        assert_eq!(server.requests(Method::PATCH, CONFIG_PATH).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_without_finalizer() {
+        let (ctx, server) = mock_context(|_| None);
+
+        // Cluster import finalizer does not trigger the uninstall
+        let mut config = deleted_config(vec!["fleet.addons.cluster.x-k8s.io".into()]);
+        let action = config.uninstall(ctx).await.unwrap();
+        assert_eq!(action, Action::await_change());
+        assert!(server
+            .requests(Method::GET, "/apis/fleet.cattle.io/v1alpha1/clusters")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_removes_imported_clusters() {
+        let (ctx, server) = mock_context(|request| match request.method {
+            Method::GET => Some(list(vec![
+                fleet_cluster(
+                    "labeled",
+                    json!({"cluster.x-k8s.io/cluster-name": "labeled"}),
+                    json!([]),
+                ),
+                fleet_cluster(
+                    "owned",
+                    json!({}),
+                    json!([{
+                        "apiVersion": "cluster.x-k8s.io/v1beta1",
+                        "kind": "Cluster",
+                        "name": "owned",
+                        "uid": "uid",
+                    }]),
+                ),
+                fleet_cluster("local", json!({}), json!([])),
+            ])),
+            Method::DELETE => Some(fleet_cluster("deleted", json!({}), json!([]))),
+            _ => None,
+        });
+
+        let mut config = deleted_config(vec![FLEET_UNINSTALL_FINALIZER.into()]);
+        let action = config.uninstall(ctx).await.unwrap();
+        assert_eq!(action, Action::requeue(StdDuration::from_secs(5)));
+
+        let deleted = |name: &str| {
+            server.requests(
+                Method::DELETE,
+                &format!("/apis/fleet.cattle.io/v1alpha1/namespaces/default/clusters/{name}"),
+            )
+        };
+        assert_eq!(deleted("labeled").len(), 1);
+        assert_eq!(deleted("owned").len(), 1);
+        assert!(deleted("local").is_empty());
+
+        // Finalizer is kept until the imported clusters are gone
+        assert!(server.requests(Method::PATCH, CONFIG_PATH).is_empty());
+        let condition = config.status.unwrap().conditions.pop().unwrap();
+        assert_eq!(condition.type_, "ImportedClustersRemoved");
+        assert_eq!(condition.status, "False");
+    }
+
+    #[tokio::test]
+    async fn test_rollback_without_revision() {
+        let (ctx, server) = mock_context(|_| None);
+        let mut config = FleetAddonConfig::default();
+
+        // Missing revision skips the helm rollback, reporting the failure only
+        config
+            .rollback(ctx, &FleetChart::default(), "fleet", None, "failed".into())
+            .await
+            .unwrap();
+
+        let conditions = config.status.unwrap().conditions;
+        let rolled_back = conditions.iter().find(|c| c.type_ == "RolledBack").unwrap();
+        assert_eq!(rolled_back.status, "False");
+        assert_eq!(rolled_back.reason, "NoPreviousRevision");
+        assert_eq!(server.events(), ["UpgradeFailed", "RolledBack"]);
+    }
+
+    #[tokio::test]
+    async fn test_verify_rollout_missing_deployment() {
+        let (ctx, server) = mock_context(|_| None);
+        let mut config = FleetAddonConfig::default();
+        config.upgrade_condition("Unknown", "RolloutInProgress", "".into());
+
+        // Deployment not created yet is awaited until the rollout timeout
+        let action = config
+            .verify_rollout(ctx, &FleetChart::default())
+            .await
+            .unwrap();
+        assert_eq!(action, Some(Action::requeue(ROLLOUT_CHECK_INTERVAL)));
+        assert!(server.events().is_empty());
+        assert!(config.rollout_started().is_some());
+    }
 }
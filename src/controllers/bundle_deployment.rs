@@ -0,0 +1,427 @@
+use crate::api::capi_cluster::{Cluster, ClusterCondition, ConditionStatus};
+use crate::api::fleet_bundle_deployment::BundleDeployment;
+use crate::api::fleet_cluster::{self, FLEET_CLUSTER_LABEL, FLEET_CLUSTER_NAMESPACE_LABEL};
+use crate::telemetry;
+
+use chrono::{DateTime, Utc};
+use kube::api::ListParams;
+use kube::runtime::controller::Action;
+use kube::runtime::events::{Event, EventType};
+use kube::{Api, Resource, ResourceExt};
+use tracing::field::display;
+use tracing::{info, instrument, Span};
+
+use std::sync::Arc;
+
+use super::controller::{set_cluster_condition, Context};
+use super::{AddonStatusError, AddonStatusResult};
+
+pub static FLEET_ADDONS_READY_CONDITION: &str = "FleetAddonsReady";
+pub static FLEET_AGENT_CONNECTED_CONDITION: &str = "FleetAgentConnected";
+pub static FLEET_READY_CONDITION: &str = "Ready";
+
+/// BundleSummary aggregates the state of all BundleDeployments targeting a Fleet cluster.
+#[derive(Default, Debug, PartialEq)]
+pub struct BundleSummary {
+    pub desired: usize,
+    pub ready: usize,
+    pub modified: usize,
+    pub not_ready: usize,
+    not_ready_bundles: Vec<String>,
+}
+
+impl<'a> FromIterator<&'a BundleDeployment> for BundleSummary {
+    fn from_iter<T: IntoIterator<Item = &'a BundleDeployment>>(iter: T) -> Self {
+        let mut summary = Self::default();
+        for deployment in iter {
+            let status = deployment.status.clone().unwrap_or_default();
+            let ready = status.ready.is_some_and(|ready| ready);
+            let modified = status
+                .non_modified
+                .is_some_and(|non_modified| !non_modified);
+
+            summary.desired += 1;
+            match (ready, modified) {
+                (true, false) => summary.ready += 1,
+                (_, true) => summary.modified += 1,
+                (false, false) => summary.not_ready += 1,
+            }
+
+            if !ready || modified {
+                summary.not_ready_bundles.push(deployment.name_any());
+            }
+        }
+
+        summary.not_ready_bundles.sort();
+        summary
+    }
+}
+
+impl BundleSummary {
+    pub fn ready(&self) -> bool {
+        self.ready == self.desired
+    }
+
+    pub fn to_condition(&self) -> ClusterCondition {
+        if self.desired == 0 {
+            return ClusterCondition {
+                type_: FLEET_ADDONS_READY_CONDITION.into(),
+                status: ConditionStatus::Unknown,
+                reason: "NoBundles".into(),
+                message: "No bundles are targeting the cluster".into(),
+            };
+        }
+
+        let Self {
+            desired,
+            ready,
+            modified,
+            not_ready,
+            ..
+        } = self;
+        let mut message =
+            format!("{ready}/{desired} bundles ready, {modified} modified, {not_ready} not ready");
+        if !self.not_ready_bundles.is_empty() {
+            message = format!("{message}: {}", self.not_ready_bundles.join(", "));
+        }
+
+        ClusterCondition {
+            type_: FLEET_ADDONS_READY_CONDITION.into(),
+            status: self.ready().into(),
+            reason: match (self.ready(), self.modified > 0) {
+                (true, _) => "BundlesReady",
+                (false, true) => "BundlesModified",
+                (false, false) => "BundlesNotReady",
+            }
+            .into(),
+            message,
+        }
+    }
+}
+
+impl fleet_cluster::Cluster {
+    #[instrument(skip_all, fields(reconcile_id, name = self.name_any(), namespace = self.namespace()), err)]
+    pub async fn reconcile_addons(self: Arc<Self>, ctx: Arc<Context>) -> crate::Result<Action> {
+        let _current = Span::current().record("reconcile_id", display(telemetry::get_trace_id()));
+        let action = self.sync_agent_status(ctx.clone()).await?;
+        self.sync_addons_status(ctx).await?;
+        Ok(action)
+    }
+
+    /// Time of the last agent check-in, reported in the Fleet cluster status.
+    pub(crate) fn agent_last_seen(&self) -> Option<DateTime<Utc>> {
+        let last_seen = self.status.as_ref()?.agent.as_ref()?.last_seen.as_ref()?;
+        let last_seen = DateTime::parse_from_rfc3339(last_seen).ok()?.to_utc();
+        // Zero time is reported for agents which never checked in
+        (last_seen > DateTime::UNIX_EPOCH).then_some(last_seen)
+    }
+
+    /// Project the agent connectivity and readiness into the CAPI Cluster condition.
+    /// A connected agent is reported only once it runs the requested agent generation
+    /// and the Fleet cluster is `Ready`.
+    pub(crate) fn agent_condition(
+        &self,
+        now: DateTime<Utc>,
+        timeout: chrono::Duration,
+    ) -> ClusterCondition {
+        let condition = |status: bool, reason: &str, message: String| ClusterCondition {
+            type_: FLEET_AGENT_CONNECTED_CONDITION.into(),
+            status: status.into(),
+            reason: reason.into(),
+            message,
+        };
+
+        let Some(last_seen) = self.agent_last_seen() else {
+            return condition(
+                false,
+                "AgentNotConnected",
+                "Agent has not checked in yet".into(),
+            );
+        };
+
+        if now - last_seen > timeout {
+            return condition(
+                false,
+                "AgentDisconnected",
+                format!("Agent last seen at {}", last_seen.to_rfc3339()),
+            );
+        }
+
+        let status = self.status.as_ref();
+        let requested = self.spec.redeploy_agent_generation.unwrap_or_default();
+        let deployed = status
+            .and_then(|s| s.agent_deployed_generation)
+            .unwrap_or_default();
+        if deployed < requested {
+            return condition(
+                false,
+                "AgentRedeploying",
+                format!(
+                    "Agent generation {deployed} is deployed, waiting for generation {requested}"
+                ),
+            );
+        }
+
+        let ready = status
+            .and_then(|s| s.conditions.as_ref())
+            .and_then(|c| c.iter().find(|c| c.r#type == FLEET_READY_CONDITION));
+        if ready.is_none_or(|ready| ready.status != "True") {
+            let message = ready
+                .and_then(|ready| ready.message.clone())
+                .filter(|message| !message.is_empty())
+                .unwrap_or_else(|| "Fleet cluster is not ready".into());
+            return condition(false, "AgentNotReady", message);
+        }
+
+        let summary = status.and_then(|s| s.summary.as_ref());
+        let ready = summary.and_then(|s| s.ready).unwrap_or_default();
+        let desired = summary.and_then(|s| s.desired_ready).unwrap_or_default();
+        condition(
+            true,
+            "AgentConnected",
+            format!("Agent is connected, {ready}/{desired} bundles ready"),
+        )
+    }
+
+    /// Mirror the Fleet agent connectivity onto the owning CAPI Cluster, reporting transitions as events.
+    /// Connected agents are re-checked once the check-in is overdue.
+    async fn sync_agent_status(&self, ctx: Arc<Context>) -> AddonStatusResult<Action> {
+        let Some(cluster_name) = self.capi_cluster_name() else {
+            return Ok(Action::await_change());
+        };
+
+        if self.metadata.deletion_timestamp.is_some() {
+            return Ok(Action::await_change());
+        }
+
+        let namespace = self.namespace().unwrap_or_default();
+        let Some(cluster) = Api::<Cluster>::namespaced(ctx.client.clone(), &namespace)
+            .get_opt(&cluster_name)
+            .await
+            .map_err(AddonStatusError::ClusterLookup)?
+        else {
+            return Ok(Action::await_change());
+        };
+
+        let timeout = ctx.config().await?.agent_disconnect_timeout();
+        let now = Utc::now();
+        let condition = self.agent_condition(now, timeout);
+        let status = cluster.status.as_ref();
+        let reported = status.is_some_and(|s| s.has_condition(FLEET_AGENT_CONNECTED_CONDITION));
+        let was_connected =
+            status.is_some_and(|s| s.condition_true(FLEET_AGENT_CONNECTED_CONDITION));
+
+        set_cluster_condition(ctx.clone(), &namespace, &cluster_name, &condition)
+            .await
+            .map_err(AddonStatusError::ClusterStatusPatch)?;
+
+        if (reported && was_connected != condition.is_true()) || (!reported && condition.is_true())
+        {
+            info!("Fleet agent connectivity changed: {}", condition.message);
+            let type_ = match condition.is_true() {
+                true => EventType::Normal,
+                false => EventType::Warning,
+            };
+            match ctx
+                .diagnostics
+                .read()
+                .await
+                .recorder(ctx.client.clone())
+                .publish(
+                    &Event {
+                        type_,
+                        reason: condition.reason.clone(),
+                        note: Some(condition.message.clone()),
+                        action: "AgentStatus".into(),
+                        secondary: None,
+                    },
+                    &cluster.object_ref(&()),
+                )
+                .await
+            {
+                // Ignore forbidden errors on event creation
+                Err(kube::Error::Api(e)) if &e.reason == "Forbidden" => (),
+                e => e.map_err(AddonStatusError::Event)?,
+            };
+        }
+
+        let overdue = self
+            .agent_last_seen()
+            .filter(|_| condition.is_true())
+            .and_then(|last_seen| (last_seen + timeout - now).to_std().ok());
+        Ok(overdue.map_or_else(Action::await_change, |overdue| {
+            Action::requeue(overdue + std::time::Duration::from_secs(1))
+        }))
+    }
+
+    /// Roll up the BundleDeployments state for the Fleet cluster into the owning CAPI Cluster condition.
+    async fn sync_addons_status(&self, ctx: Arc<Context>) -> AddonStatusResult<Action> {
+        let Some(cluster_name) = self.capi_cluster_name() else {
+            return Ok(Action::await_change());
+        };
+
+        if self.metadata.deletion_timestamp.is_some() {
+            return Ok(Action::await_change());
+        }
+
+        let name = self.name_any();
+        let namespace = self.namespace().unwrap_or_default();
+        let deployments = Api::<BundleDeployment>::all(ctx.client.clone())
+            .list(&ListParams::default().labels(&format!(
+                "{FLEET_CLUSTER_LABEL}={name},{FLEET_CLUSTER_NAMESPACE_LABEL}={namespace}"
+            )))
+            .await
+            .map_err(AddonStatusError::BundleDeploymentLookup)?;
+
+        let summary: BundleSummary = deployments.items.iter().collect();
+        let condition = summary.to_condition();
+        if set_cluster_condition(ctx, &namespace, &cluster_name, &condition)
+            .await
+            .map_err(AddonStatusError::ClusterStatusPatch)?
+        {
+            info!(
+                "Updated {FLEET_ADDONS_READY_CONDITION} condition: {}",
+                condition.message
+            );
+        }
+
+        Ok(Action::await_change())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use kube::api::ObjectMeta;
+    use serde_json::json;
+
+    use crate::api::capi_cluster::{ConditionStatus, CLUSTER_NAME_LABEL};
+    use crate::api::fleet_bundle_deployment::{BundleDeployment, BundleDeploymentStatus};
+    use crate::api::fleet_cluster;
+
+    use super::BundleSummary;
+
+    fn deployment(name: &str, ready: bool, non_modified: bool) -> BundleDeployment {
+        BundleDeployment {
+            metadata: ObjectMeta {
+                name: Some(name.into()),
+                ..Default::default()
+            },
+            status: Some(BundleDeploymentStatus {
+                ready: Some(ready),
+                non_modified: Some(non_modified),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bundle_summary() {
+        let deployments = [
+            deployment("ready", true, true),
+            deployment("modified", true, false),
+            deployment("not-ready", false, true),
+            BundleDeployment {
+                metadata: ObjectMeta {
+                    name: Some("pending".into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ];
+
+        let summary: BundleSummary = deployments.iter().collect();
+        assert_eq!(summary.desired, 4);
+        assert_eq!(summary.ready, 1);
+        assert_eq!(summary.modified, 1);
+        assert_eq!(summary.not_ready, 2);
+
+        let condition = summary.to_condition();
+        assert!(!condition.is_true());
+        assert_eq!(condition.reason, "BundlesModified");
+        assert_eq!(
+            condition.message,
+            "1/4 bundles ready, 1 modified, 2 not ready: modified, not-ready, pending"
+        );
+    }
+
+    #[test]
+    fn test_agent_condition() {
+        let now = Utc::now();
+        let timeout = chrono::Duration::minutes(45);
+        let cluster = |last_seen: &str, ready: &str, generation: i64| -> fleet_cluster::Cluster {
+            serde_json::from_value(json!({
+                "metadata": {"name": "cluster"},
+                "spec": {"redeployAgentGeneration": 2},
+                "status": {
+                    "agent": {"lastSeen": last_seen},
+                    "agentDeployedGeneration": generation,
+                    "conditions": [{"type": "Ready", "status": ready, "message": ""}],
+                    "summary": {"ready": 1, "desiredReady": 2},
+                },
+            }))
+            .unwrap()
+        };
+
+        let condition = cluster("0001-01-01T00:00:00Z", "True", 2).agent_condition(now, timeout);
+        assert!(!condition.is_true());
+        assert_eq!(condition.reason, "AgentNotConnected");
+
+        let seen = (now - chrono::Duration::minutes(5)).to_rfc3339();
+        let condition = cluster(&seen, "True", 2).agent_condition(now, timeout);
+        assert!(condition.is_true());
+        assert_eq!(condition.message, "Agent is connected, 1/2 bundles ready");
+
+        let condition = cluster(&seen, "True", 1).agent_condition(now, timeout);
+        assert!(!condition.is_true());
+        assert_eq!(condition.reason, "AgentRedeploying");
+        assert_eq!(
+            condition.message,
+            "Agent generation 1 is deployed, waiting for generation 2"
+        );
+
+        let condition = cluster(&seen, "False", 2).agent_condition(now, timeout);
+        assert!(!condition.is_true());
+        assert_eq!(condition.reason, "AgentNotReady");
+        assert_eq!(condition.message, "Fleet cluster is not ready");
+
+        let seen = (now - chrono::Duration::hours(1)).to_rfc3339();
+        let condition = cluster(&seen, "True", 2).agent_condition(now, timeout);
+        assert!(!condition.is_true());
+        assert_eq!(condition.reason, "AgentDisconnected");
+
+        let condition = cluster(&seen, "True", 2).agent_condition(now, chrono::Duration::hours(2));
+        assert!(condition.is_true());
+    }
+
+    #[test]
+    fn test_empty_bundle_summary() {
+        let summary: BundleSummary = [].iter().collect();
+        let condition = summary.to_condition();
+        assert_eq!(condition.status, ConditionStatus::Unknown);
+        assert_eq!(condition.reason, "NoBundles");
+    }
+
+    #[test]
+    fn test_capi_cluster_name() {
+        let cluster = |labels: serde_json::Value, secret: &str| -> fleet_cluster::Cluster {
+            serde_json::from_value(json!({
+                "metadata": {"name": "cluster", "labels": labels},
+                "spec": {"kubeConfigSecret": secret},
+            }))
+            .unwrap()
+        };
+
+        let labelled = cluster(json!({CLUSTER_NAME_LABEL: "capi"}), "capi-fleet-kubeconfig");
+        assert_eq!(labelled.capi_cluster_name().as_deref(), Some("capi"));
+
+        // Clusters imported before the label was set are matched by the kubeconfig secret
+        let legacy = cluster(json!({}), "cluster-kubeconfig");
+        assert_eq!(legacy.capi_cluster_name().as_deref(), Some("cluster"));
+
+        assert!(cluster(json!({}), "other-kubeconfig")
+            .capi_cluster_name()
+            .is_none());
+    }
+}
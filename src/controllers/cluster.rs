@@ -1,44 +1,95 @@
 use crate::api::bundle_namespace_mapping::BundleNamespaceMapping;
-use crate::api::capi_cluster::Cluster;
+use crate::api::capi_cluster::{
+    class_group_name, machine_ready, provider_group_name, version_group_name, AgentOverrides,
+    Cluster, ClusterCondition, ConditionStatus, CLUSTER_NAME_LABEL, CONTROL_PLANE_LABEL,
+    IMPORT_PROFILE_LABEL,
+};
 
-use crate::api::fleet_addon_config::FleetAddonConfig;
-use crate::api::fleet_cluster::{self};
+use crate::api::fleet_addon_config::{
+    unique_name, AdoptionPolicy, ClusterConfig, DeimportPolicy, Drain, FleetAddonConfig,
+};
+use crate::api::fleet_bundle_deployment::BundleDeployment;
+use crate::api::fleet_cluster::{self, FLEET_CLUSTER_LABEL, FLEET_CLUSTER_NAMESPACE_LABEL};
 
 #[cfg(feature = "agent-initiated")]
 use crate::api::fleet_cluster_registration_token::ClusterRegistrationToken;
-use crate::api::fleet_clustergroup::ClusterGroup;
+use crate::api::fleet_clustergroup::{
+    ClusterGroup, CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL, CONTROL_PLANE_KIND_LABEL,
+    INFRASTRUCTURE_KIND_LABEL, KUBERNETES_MINOR_VERSION_LABEL,
+};
 use crate::controllers::addon_config::to_dynamic_event;
+use chrono::Utc;
+use cluster_api_rs::capi_machine::Machine;
 use futures::StreamExt as _;
-use k8s_openapi::api::core::v1::Namespace;
-use kube::api::{ApiResource, ListParams, Object, PatchParams};
+use k8s_openapi::api::core::v1::{ConfigMap, Namespace, ObjectReference, Secret};
+use kube::api::{ApiResource, ListParams, Object, Patch, PatchParams};
 
 use kube::client::scope;
+use kube::core::{NamespaceResourceScope, PartialObjectMeta};
+use kube::runtime::events::{Event, EventType};
 use kube::runtime::watcher::{self, Config};
 use kube::{api::ResourceExt, runtime::controller::Action, Resource};
 use kube::{Api, Client};
 #[cfg(feature = "agent-initiated")]
 use rand::distr::{Alphanumeric, SampleString as _};
-use serde::Serialize;
-use serde_json::Value;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
 use tracing::info;
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::controller::{
-    fetch_config, get_or_create, patch, Context, FleetBundle, FleetController,
+    get_or_create, patch, plan, plan_delete, set_cluster_condition, Context, FleetBundle,
+    FleetController, FLEET_FINALIZER,
+};
+use super::kubeconfig::{
+    remove_service_account_kubeconfig, secret_hash, sync_service_account_kubeconfig,
+    ADMIN_KUBECONFIG_HASH_ANNOTATION, KUBECONFIG_HASH_ANNOTATION,
 };
-use super::{BundleResult, ClusterSyncError, ClusterSyncResult};
+use super::{
+    BundleError, BundleResult, ClusterSyncError, ClusterSyncResult, DeimportError, DeimportResult,
+    DrainError, DrainResult, LabelCheckError,
+};
+
+/// Annotation marking Fleet clusters paused by the addon provider
+pub static FLEET_PAUSED_ANNOTATION: &str = "paused.fleet.addons.cluster.x-k8s.io";
+
+/// Condition reporting whether Fleet delivery to the cluster is active, or paused with the CAPI Cluster
+pub static DELIVERY_CONDITION: &str = "FleetDelivery";
+
+/// Condition reporting a conflict with a pre-existing Fleet cluster, not managed for the CAPI cluster
+pub static ADOPTION_CONDITION: &str = "FleetClusterAdoption";
 
-pub static CONTROLPLANE_READY_CONDITION: &str = "ControlPlaneReady";
+/// Condition reporting the readiness gate requirements the cluster is waiting for before import
+pub static READINESS_GATE_CONDITION: &str = "FleetReadinessGate";
+
+/// Condition reporting the Fleet workloads removal from a deleted cluster
+pub static DRAIN_CONDITION: &str = "FleetDrain";
+
+/// Condition reporting invalid Fleet agent overrides for the cluster
+pub static AGENT_OVERRIDES_CONDITION: &str = "FleetAgentOverrides";
+
+/// Condition reporting an invalid or already taken Fleet cluster name
+pub static NAME_CONDITION: &str = "FleetClusterName";
+
+/// Condition reporting a ClusterGroup name already taken by a different ClusterClass
+pub static GROUP_NAME_CONDITION: &str = "FleetClusterGroupName";
+
+/// Interval between the BundleDeployments removal checks during the drain
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_secs(10);
 
 pub struct FleetClusterBundle {
     template_sources: TemplateSources,
     fleet: fleet_cluster::Cluster,
     fleet_group: Option<ClusterGroup>,
+    provider_groups: Vec<ClusterGroup>,
+    version_group: Option<ClusterGroup>,
     mapping: Option<BundleNamespaceMapping>,
     #[cfg(feature = "agent-initiated")]
     cluster_registration_token: Option<ClusterRegistrationToken>,
-    config: FleetAddonConfig,
+    config: ClusterConfig,
+    dry_run: bool,
 }
 
 pub struct TemplateSources(Cluster);
@@ -101,8 +152,12 @@ impl FleetBundle for FleetClusterBundle {
             cluster.spec.template_values = Some(template);
         }
 
+        if self.dry_run {
+            return self.plan(ctx).await;
+        }
+
         if let Some(mapping) = self.mapping.as_mut() {
-            if self.config.cluster_patch_enabled() {
+            if self.config.patch_enabled() {
                 let cluster_name = cluster.name_any();
                 patch(
                     ctx.clone(),
@@ -118,7 +173,25 @@ impl FleetBundle for FleetClusterBundle {
             };
         }
 
-        match self.config.cluster_patch_enabled() {
+        let mut action = Action::await_change();
+        if let Some(settings) = &self.config.service_account_kubeconfig {
+            let capi_cluster = &self.template_sources.0;
+            let secret = capi_cluster.fleet_kubeconfig_secret_name(&self.config);
+            let rotate_in =
+                sync_service_account_kubeconfig(ctx.clone(), capi_cluster, settings, &secret)
+                    .await?;
+            action = Action::requeue(rotate_in);
+        }
+
+        // Version group of the previous Kubernetes version may become stale after the upgrade
+        let previous_version = match self.version_group.is_some() && self.config.patch_enabled() {
+            true => changed_version(ctx.clone(), cluster)
+                .await
+                .map_err(ClusterSyncError::VersionGroupCleanup)?,
+            false => None,
+        };
+
+        match self.config.patch_enabled() {
             true => {
                 patch(
                     ctx.clone(),
@@ -130,6 +203,17 @@ impl FleetBundle for FleetClusterBundle {
             false => get_or_create(ctx.clone(), cluster).await?,
         };
 
+        // Disabled ServiceAccount kubeconfig is removed once the Fleet cluster uses the admin one
+        if self.config.service_account_kubeconfig.is_none() && self.config.patch_enabled() {
+            let capi_cluster = &self.template_sources.0;
+            remove_service_account_kubeconfig(
+                ctx.clone(),
+                &capi_cluster.namespace().unwrap_or_default(),
+                &capi_cluster.name_any(),
+            )
+            .await?;
+        }
+
         #[cfg(feature = "agent-initiated")]
         if let Some(cluster_registration_token) = self.cluster_registration_token.as_ref() {
             get_or_create(ctx.clone(), cluster_registration_token).await?;
@@ -137,7 +221,7 @@ impl FleetBundle for FleetClusterBundle {
 
         if let Some(group) = self.fleet_group.as_mut() {
             let cluster_name = self.fleet.name_any();
-            if self.config.cluster_patch_enabled() {
+            if self.config.patch_enabled() {
                 patch(
                     ctx.clone(),
                     group,
@@ -148,10 +232,39 @@ impl FleetBundle for FleetClusterBundle {
             };
         }
 
-        Ok(Action::await_change())
+        let patch_enabled = self.config.patch_enabled();
+        let groups = self
+            .provider_groups
+            .iter_mut()
+            .chain(self.version_group.as_mut());
+        for group in groups.filter(|_| patch_enabled) {
+            let cluster_name = self.fleet.name_any();
+            patch(
+                ctx.clone(),
+                group,
+                &PatchParams::apply(&format!("cluster-{cluster_name}-addon-provider-fleet")),
+            )
+            .await
+            .map_err(ClusterSyncError::GroupPatchError)?;
+        }
+
+        if let Some(previous) = previous_version {
+            let ns = self.fleet.namespace().unwrap_or_default();
+            delete_unused_version_group(ctx.clone(), &ns, &previous)
+                .await
+                .map_err(ClusterSyncError::VersionGroupCleanup)?;
+        }
+
+        Ok(action)
     }
 
     async fn cleanup(&mut self, ctx: Arc<Context>) -> Result<Action, super::SyncError> {
+        if let Some(drain) = self.config.drain.clone() {
+            if let Some(pending) = self.drain(ctx.clone(), &drain).await? {
+                return Ok(pending);
+            }
+        }
+
         if let Some(mapping) = self.mapping.as_ref() {
             let ns = mapping.namespace();
             let other_clusters = ctx
@@ -172,6 +285,12 @@ impl FleetBundle for FleetClusterBundle {
                 return Ok(Action::await_change());
             }
 
+            if self.dry_run {
+                let owner = self.template_sources.0.object_ref(&());
+                plan_delete(ctx, mapping, &owner).await?;
+                return Ok(Action::await_change());
+            }
+
             Api::<BundleNamespaceMapping>::namespaced(ctx.client.clone(), &ns.unwrap_or_default())
                 .delete(&mapping.name_any(), &Default::default())
                 .await?;
@@ -181,42 +300,882 @@ impl FleetBundle for FleetClusterBundle {
     }
 }
 
+impl FleetClusterBundle {
+    /// Delete the Fleet Cluster and wait for its BundleDeployments removal, until the drain timeout
+    /// expires. Returns the requeue action while the workloads are still present.
+    async fn drain(&self, ctx: Arc<Context>, drain: &Drain) -> DrainResult<Option<Action>> {
+        let cluster = &self.template_sources.0;
+        let ns = self.fleet.namespace().unwrap_or_default();
+        let api = Api::<fleet_cluster::Cluster>::namespaced(ctx.client.clone(), &ns);
+
+        // Fleet clusters not managed for this cluster are left untouched
+        let Some(name) = self.imported_name(&api).await? else {
+            return Ok(None);
+        };
+
+        if self.dry_run {
+            let mut fleet = self.fleet.clone();
+            fleet.metadata.name = Some(name);
+            return plan_delete(ctx, &fleet, &cluster.object_ref(&()))
+                .await
+                .map(|_| None)
+                .map_err(DrainError::FleetDelete);
+        }
+
+        let deleted = match api.delete(&name, &Default::default()).await {
+            Err(kube::Error::Api(e)) if e.code == 404 => false,
+            e => {
+                e.map_err(DrainError::FleetDelete)?;
+                true
+            }
+        };
+
+        let remaining = Api::<BundleDeployment>::all(ctx.client.clone())
+            .list_metadata(&ListParams::default().labels(&format!(
+                "{FLEET_CLUSTER_LABEL}={name},{FLEET_CLUSTER_NAMESPACE_LABEL}={ns}"
+            )))
+            .await
+            .map_err(DrainError::Lookup)?
+            .items
+            .len();
+
+        // Drain started on a previous reconcile is reported as done, while clusters which were
+        // never imported have nothing to drain
+        let draining = cluster
+            .status
+            .as_ref()
+            .is_some_and(|status| status.has_condition(DRAIN_CONDITION));
+        if remaining == 0 {
+            if deleted || draining {
+                let note = format!("Fleet workloads removed from cluster {name}");
+                cluster
+                    .publish(ctx, EventType::Normal, "Drained", note)
+                    .await?;
+            }
+            return Ok(None);
+        }
+
+        let started = cluster
+            .meta()
+            .deletion_timestamp
+            .as_ref()
+            .map_or_else(Utc::now, |t| t.0);
+        if drain.expired(started, Utc::now()) {
+            let note = format!(
+                "Drain timeout of {}s expired with {remaining} BundleDeployments remaining, releasing cluster",
+                drain.timeout_seconds()
+            );
+            cluster
+                .publish(ctx, EventType::Warning, "DrainTimeout", note)
+                .await?;
+            return Ok(None);
+        }
+
+        // Condition marks the drain start, so the event is published only once
+        let condition = ClusterCondition {
+            type_: DRAIN_CONDITION.into(),
+            status: ConditionStatus::False,
+            reason: "Draining".into(),
+            message: format!("Waiting for Fleet cluster {name} BundleDeployments removal"),
+        };
+        let ns = cluster.namespace().unwrap_or_default();
+        let transitioned = set_cluster_condition(ctx.clone(), &ns, &cluster.name_any(), &condition)
+            .await
+            .map_err(DrainError::Condition)?;
+        if transitioned {
+            let note = format!("Waiting for {remaining} BundleDeployments removal");
+            cluster
+                .publish(ctx, EventType::Normal, "Draining", note)
+                .await?;
+        }
+
+        Ok(Some(Action::requeue(DRAIN_CHECK_INTERVAL)))
+    }
+
+    /// Name of the Fleet cluster imported for the CAPI cluster, which differs from the generated one
+    /// when it was renamed on adoption. `None` if the name is taken by an unmanaged Fleet cluster.
+    async fn imported_name(
+        &self,
+        api: &Api<fleet_cluster::Cluster>,
+    ) -> DrainResult<Option<String>> {
+        let cluster = &self.template_sources.0;
+        let name = self.fleet.name_any();
+        let existing = api.get_opt(&name).await.map_err(DrainError::FleetLookup)?;
+        if existing
+            .as_ref()
+            .is_some_and(|existing| cluster.owns_fleet_cluster(existing))
+        {
+            return Ok(Some(name));
+        }
+
+        let labelled = api
+            .list_metadata(
+                &ListParams::default()
+                    .labels(&format!("{CLUSTER_NAME_LABEL}={}", cluster.name_any())),
+            )
+            .await
+            .map_err(DrainError::FleetLookup)?;
+        Ok(match labelled.items.first() {
+            Some(imported) => Some(imported.name_any()),
+            None => existing.is_none().then_some(name),
+        })
+    }
+
+    /// Report planned changes to the Fleet resources for the cluster, without applying them.
+    async fn plan(&mut self, ctx: Arc<Context>) -> ClusterSyncResult<Action> {
+        let owner = self.template_sources.0.object_ref(&());
+        let cluster_name = self.fleet.name_any();
+        let patch_enabled = self.config.patch_enabled();
+        let group_pp = PatchParams::apply(&format!("cluster-{cluster_name}-addon-provider-fleet"));
+
+        if let Some(mapping) = self.mapping.as_mut().filter(|_| patch_enabled) {
+            plan(ctx.clone(), mapping, Some(&group_pp), &owner)
+                .await
+                .map_err(ClusterSyncError::BundleNamespaceMappingError)?;
+        }
+
+        let pp = PatchParams::apply("addon-provider-fleet");
+        plan(
+            ctx.clone(),
+            &mut self.fleet,
+            patch_enabled.then_some(&pp),
+            &owner,
+        )
+        .await?;
+
+        #[cfg(feature = "agent-initiated")]
+        if let Some(cluster_registration_token) = self.cluster_registration_token.as_mut() {
+            plan(ctx.clone(), cluster_registration_token, None, &owner).await?;
+        }
+
+        if let Some(group) = self.fleet_group.as_mut().filter(|_| patch_enabled) {
+            plan(ctx.clone(), group, Some(&group_pp), &owner)
+                .await
+                .map_err(ClusterSyncError::GroupPatchError)?;
+        }
+
+        let groups = self
+            .provider_groups
+            .iter_mut()
+            .chain(self.version_group.as_mut());
+        for group in groups.filter(|_| patch_enabled) {
+            plan(ctx.clone(), group, Some(&group_pp), &owner)
+                .await
+                .map_err(ClusterSyncError::GroupPatchError)?;
+        }
+
+        Ok(Action::await_change())
+    }
+}
+
 impl FleetController for Cluster {
     type Bundle = FleetClusterBundle;
 
-    async fn to_bundle(&self, ctx: Arc<Context>) -> BundleResult<Option<FleetClusterBundle>> {
-        let config = fetch_config(ctx.client.clone()).await?;
+    /// Paused clusters are imported once CAPI unpauses them.
+    fn import_deferred(&self) -> bool {
+        self.paused()
+    }
 
+    async fn to_bundle(
+        &self,
+        ctx: Arc<Context>,
+        config: Option<&FleetAddonConfig>,
+    ) -> BundleResult<Option<FleetClusterBundle>> {
+        // Imported clusters are removed while Fleet is being uninstalled, skip re-importing them
+        if config.is_some_and(FleetAddonConfig::uninstalling) {
+            return Ok(None);
+        }
+        let config = config.cloned().unwrap_or_default();
         if !config.cluster_operations_enabled() {
             return Ok(None);
         }
 
-        if self.cluster_ready().is_none() {
+        let namespace: Namespace = Api::all(ctx.client.clone())
+            .get(&self.namespace().unwrap_or_default())
+            .await
+            .map_err(LabelCheckError::from)?;
+        let Some((profile, cluster_config)) = config
+            .import_profile(self.labels(), namespace.labels())
+            .map_err(LabelCheckError::from)?
+        else {
+            return Ok(None);
+        };
+
+        // Import gates and writes are skipped on deletion, so the Fleet resources are still cleaned up
+        let deleted = self.meta().deletion_timestamp.is_some();
+        let dry_run = ctx.dry_run || config.dry_run_enabled();
+        if cluster_config.pause_fleet_cluster() && !dry_run && !deleted {
+            self.pause_fleet_cluster(ctx.clone()).await?;
+        }
+
+        // Paused clusters are not reconciled until CAPI unpauses them, deletion proceeds as usual
+        if self.paused() && !deleted {
+            info!("Cluster is paused, skipping Fleet resources reconciliation");
             return Ok(None);
         }
 
+        if !deleted
+            && !self
+                .readiness_gate(ctx.clone(), cluster_config, dry_run)
+                .await?
+        {
+            return Ok(None);
+        }
+
+        let overrides = self.agent_overrides(ctx.clone(), dry_run).await?;
+        let cluster_config = cluster_config.with_overrides(overrides);
+        let mut fleet = match self.to_cluster(Some(&cluster_config)) {
+            Ok(fleet) => fleet,
+            Err(e) => {
+                let condition = name_condition(NAME_CONDITION, "InvalidName", e.to_string());
+                self.report_condition(ctx, &condition, dry_run).await?;
+                return Ok(None);
+            }
+        };
+        let observed_version = self.observed_version(ctx.client.clone()).await;
+        fleet
+            .labels_mut()
+            .extend(self.version_labels(observed_version.as_deref()));
+        fleet
+            .labels_mut()
+            .insert(IMPORT_PROFILE_LABEL.to_string(), profile.to_string());
+
+        if !deleted {
+            if let Some(note) = self.name_collision(ctx.clone(), &fleet).await? {
+                let condition = name_condition(NAME_CONDITION, "NameCollision", note);
+                self.report_condition(ctx, &condition, dry_run).await?;
+                return Ok(None);
+            }
+            self.report_condition(ctx.clone(), &name_available(NAME_CONDITION), dry_run)
+                .await?;
+
+            if !self
+                .adopt(ctx.clone(), &mut fleet, &cluster_config, dry_run)
+                .await?
+            {
+                return Ok(None);
+            }
+
+            self.kubeconfig_rotation(ctx.clone(), &mut fleet, &cluster_config, dry_run)
+                .await?;
+        }
+
+        let mut fleet_group = self.to_group(Some(&cluster_config));
+        let collision = match &fleet_group {
+            Some(group) => self.group_collision(ctx.clone(), group).await?,
+            None => None,
+        };
+        let condition = match collision {
+            Some(note) => {
+                fleet_group = None;
+                name_condition(GROUP_NAME_CONDITION, "NameCollision", note)
+            }
+            None => name_available(GROUP_NAME_CONDITION),
+        };
+        self.report_condition(ctx.clone(), &condition, dry_run)
+            .await?;
+
         Ok(Some(FleetClusterBundle {
             template_sources: TemplateSources::new(self),
-            fleet: self.to_cluster(config.spec.cluster.as_ref()),
-            fleet_group: self.to_group(config.spec.cluster.as_ref()),
-            mapping: self.to_bundle_ns_mapping(config.spec.cluster.as_ref()),
+            fleet,
+            fleet_group,
+            provider_groups: self.to_provider_groups(Some(&cluster_config)),
+            version_group: self
+                .to_version_group(Some(&cluster_config), observed_version.as_deref()),
+            mapping: self.to_bundle_ns_mapping(Some(&cluster_config)),
             #[cfg(feature = "agent-initiated")]
-            cluster_registration_token: self
-                .to_cluster_registration_token(config.spec.cluster.as_ref()),
-            config,
+            cluster_registration_token: self.to_cluster_registration_token(Some(&cluster_config)),
+            dry_run,
+            config: cluster_config,
         }))
     }
 }
 
+/// Naming condition reporting the reason the name can't be used.
+fn name_condition(type_: &str, reason: &str, message: String) -> ClusterCondition {
+    ClusterCondition {
+        type_: type_.into(),
+        status: ConditionStatus::False,
+        reason: reason.into(),
+        message,
+    }
+}
+
+/// Naming condition reporting the name is valid and not taken.
+fn name_available(type_: &str) -> ClusterCondition {
+    ClusterCondition {
+        type_: type_.into(),
+        status: ConditionStatus::True,
+        reason: "Unique".into(),
+        message: "Name is not used by other resources".into(),
+    }
+}
+
 impl Cluster {
-    pub fn cluster_ready(&self) -> Option<&Self> {
-        let status = self.status.clone()?;
-        let cp_ready = status.control_plane_ready.filter(|&ready| ready);
-        let ready_condition = status.conditions?.iter().find_map(|c| {
-            (c.type_ == CONTROLPLANE_READY_CONDITION && c.status == "True").then_some(true)
+    /// Resolve per-cluster agent overrides from the referenced ConfigMap and the cluster annotations,
+    /// with annotations taking precedence. Invalid overrides are ignored and reported in the
+    /// agent overrides condition, with a warning event when the reported errors change.
+    async fn agent_overrides(
+        &self,
+        ctx: Arc<Context>,
+        dry_run: bool,
+    ) -> BundleResult<AgentOverrides> {
+        let mut overrides = AgentOverrides::default();
+        let mut errors = vec![];
+        if let Some(name) = self.agent_config_map() {
+            let api: Api<ConfigMap> =
+                Api::namespaced(ctx.client.clone(), &self.namespace().unwrap_or_default());
+            match api
+                .get_opt(name)
+                .await
+                .map_err(BundleError::AgentOverrides)?
+            {
+                Some(config_map) => {
+                    let data = config_map.data.unwrap_or_default();
+                    (overrides, errors) =
+                        AgentOverrides::parse(data.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+                }
+                None => errors.push(format!("agent overrides ConfigMap `{name}` not found")),
+            }
+        }
+
+        let (annotated, annotation_errors) = self.annotation_overrides();
+        errors.extend(annotation_errors);
+
+        let condition = match errors.is_empty() {
+            true => ClusterCondition {
+                type_: AGENT_OVERRIDES_CONDITION.into(),
+                status: ConditionStatus::True,
+                reason: "Valid".into(),
+                message: "Agent overrides are valid".into(),
+            },
+            false => ClusterCondition {
+                type_: AGENT_OVERRIDES_CONDITION.into(),
+                status: ConditionStatus::False,
+                reason: "InvalidAgentOverride".into(),
+                message: errors.join("; "),
+            },
+        };
+        self.report_condition(ctx, &condition, dry_run).await?;
+
+        Ok(overrides.merge(annotated))
+    }
+
+    /// Check the cluster control plane readiness and the configured readiness gate. Requirements
+    /// which are not met yet are reported in the cluster readiness gate condition.
+    async fn readiness_gate(
+        &self,
+        ctx: Arc<Context>,
+        config: &ClusterConfig,
+        dry_run: bool,
+    ) -> BundleResult<bool> {
+        let Some(gate) = &config.readiness else {
+            return Ok(self.cluster_ready().is_some());
+        };
+
+        let mut pending = vec![];
+        if self.cluster_ready().is_none() {
+            pending.push("control plane readiness".to_string());
+        }
+        pending.extend(self.pending_status_requirements(gate));
+
+        let namespace = self.namespace().unwrap_or_default();
+        if gate.kubeconfig_secret.is_some_and(|required| required) {
+            let secret = self.kubeconfig_secret_name();
+            let found = Api::<Secret>::namespaced(ctx.client.clone(), &namespace)
+                .get_metadata_opt(&secret)
+                .await
+                .map_err(BundleError::Readiness)?;
+            if found.is_none() {
+                pending.push(format!("kubeconfig secret `{secret}`"));
+            }
+        }
+
+        if let Some(min_ready) = gate.min_ready_workers.filter(|&min| min > 0) {
+            let name = self.name_any();
+            let machines = Api::<Machine>::namespaced(ctx.client.clone(), &namespace)
+                .list(&ListParams::default().labels(&format!(
+                    "{CLUSTER_NAME_LABEL}={name},!{CONTROL_PLANE_LABEL}"
+                )))
+                .await
+                .map_err(BundleError::Readiness)?;
+            let ready = machines.iter().filter(|m| machine_ready(m)).count();
+            if ready < min_ready as usize {
+                pending.push(format!("ready workers {ready}/{min_ready}"));
+            }
+        }
+
+        let condition = match pending.is_empty() {
+            true => ClusterCondition {
+                type_: READINESS_GATE_CONDITION.into(),
+                status: ConditionStatus::True,
+                reason: "Ready".into(),
+                message: "All readiness requirements are met".into(),
+            },
+            false => ClusterCondition {
+                type_: READINESS_GATE_CONDITION.into(),
+                status: ConditionStatus::False,
+                reason: "Waiting".into(),
+                message: format!("Waiting for {}", pending.join(", ")),
+            },
+        };
+
+        if !dry_run {
+            set_cluster_condition(ctx, &namespace, &self.name_any(), &condition)
+                .await
+                .map_err(BundleError::Readiness)?;
+        }
+
+        Ok(pending.is_empty())
+    }
+
+    /// Pause the Fleet cluster while the CAPI cluster is paused, and resume it once the
+    /// CAPI cluster is unpaused. Fleet clusters paused by users are left untouched.
+    async fn pause_fleet_cluster(&self, ctx: Arc<Context>) -> BundleResult<()> {
+        // Fleet clusters are only updated on a pause transition, recorded in the delivery condition
+        let paused = self.paused() && self.meta().deletion_timestamp.is_none();
+        let recorded = self.status.as_ref().is_some_and(|status| {
+            status.has_condition(DELIVERY_CONDITION) && !status.condition_true(DELIVERY_CONDITION)
         });
+        if paused == recorded {
+            return Ok(());
+        }
+
+        let namespace = self.namespace().unwrap_or_default();
+        let api: Api<fleet_cluster::Cluster> = Api::namespaced(ctx.client.clone(), &namespace);
+        let imported = api
+            .list(
+                &ListParams::default().labels(&format!("{CLUSTER_NAME_LABEL}={}", self.name_any())),
+            )
+            .await
+            .map_err(BundleError::Pause)?;
+
+        for fleet in imported {
+            let paused_by_addon = fleet.annotations().contains_key(FLEET_PAUSED_ANNOTATION);
+            let (patch, reason) = match (paused, paused_by_addon) {
+                (true, false) if !fleet.spec.paused.is_some_and(|paused| paused) => (
+                    json!({
+                        "metadata": {"annotations": {FLEET_PAUSED_ANNOTATION: "true"}},
+                        "spec": {"paused": true},
+                    }),
+                    "FleetClusterPaused",
+                ),
+                (false, true) => (
+                    json!({
+                        "metadata": {"annotations": {FLEET_PAUSED_ANNOTATION: null}},
+                        "spec": {"paused": false},
+                    }),
+                    "FleetClusterResumed",
+                ),
+                _ => continue,
+            };
+
+            let name = fleet.name_any();
+            api.patch(&name, &PatchParams::default(), &Patch::Merge(patch))
+                .await
+                .map_err(BundleError::Pause)?;
+
+            let note = format!("Fleet cluster `{name}` paused: {paused}");
+            self.publish(ctx.clone(), EventType::Normal, reason, note)
+                .await?;
+        }
+
+        let condition = match paused {
+            true => ClusterCondition {
+                type_: DELIVERY_CONDITION.into(),
+                status: ConditionStatus::False,
+                reason: "Paused".into(),
+                message: "Fleet delivery is paused with the cluster".into(),
+            },
+            false => ClusterCondition {
+                type_: DELIVERY_CONDITION.into(),
+                status: ConditionStatus::True,
+                reason: "Active".into(),
+                message: "Fleet delivery is active".into(),
+            },
+        };
+        set_cluster_condition(ctx, &namespace, &self.name_any(), &condition)
+            .await
+            .map_err(BundleError::Pause)?;
+
+        Ok(())
+    }
+
+    /// Kubernetes version reported in the control plane status.
+    async fn observed_version(&self, client: Client) -> Option<String> {
+        let reference = self.spec.control_plane_ref.as_ref()?;
+        let control_plane: Object<Value, Value> = client.fetch(reference).await.ok()?;
+        let version = control_plane.status?.get("version")?.as_str()?.to_string();
+        Some(version)
+    }
+
+    /// Redeploy the Fleet agent when the kubeconfig secret used by Fleet changes, by bumping
+    /// the Fleet cluster `redeployAgentGeneration`. The secret hash is recorded on the Fleet cluster.
+    /// The ServiceAccount kubeconfig is keyed on the admin kubeconfig it was minted from, so routine
+    /// token renewals do not redeploy the agent.
+    async fn kubeconfig_rotation(
+        &self,
+        ctx: Arc<Context>,
+        fleet: &mut fleet_cluster::Cluster,
+        config: &ClusterConfig,
+        dry_run: bool,
+    ) -> BundleResult<()> {
+        let namespace = self.namespace().unwrap_or_default();
+        let secret_name = self.fleet_kubeconfig_secret_name(config);
+        let Some(secret) = Api::<Secret>::namespaced(ctx.client.clone(), &namespace)
+            .get_opt(&secret_name)
+            .await
+            .map_err(BundleError::KubeconfigRotation)?
+        else {
+            return Ok(());
+        };
+
+        let api: Api<fleet_cluster::Cluster> = Api::namespaced(ctx.client.clone(), &namespace);
+        let existing = api
+            .get_opt(&fleet.name_any())
+            .await
+            .map_err(BundleError::KubeconfigRotation)?;
+        let recorded = existing
+            .as_ref()
+            .and_then(|e| e.annotations().get(KUBECONFIG_HASH_ANNOTATION).cloned());
+        let deployed_generation = existing
+            .as_ref()
+            .and_then(|e| e.spec.redeploy_agent_generation);
+
+        let hash = config
+            .service_account_kubeconfig
+            .as_ref()
+            .and_then(|_| secret.annotations().get(ADMIN_KUBECONFIG_HASH_ANNOTATION))
+            .cloned()
+            .unwrap_or_else(|| secret_hash(&secret));
+        let rotated = recorded.as_ref().is_some_and(|recorded| *recorded != hash);
+        let generation = fleet
+            .spec
+            .redeploy_agent_generation
+            .max(deployed_generation)
+            .map(|generation| generation + i64::from(rotated))
+            .or(rotated.then_some(1));
+
+        fleet
+            .annotations_mut()
+            .insert(KUBECONFIG_HASH_ANNOTATION.to_string(), hash.clone());
+        fleet.spec.redeploy_agent_generation = generation;
+
+        // Existing clusters are updated regardless of the patch policy, to deliver the rotated credentials
+        let Some(existing) = existing.filter(|_| recorded.as_ref() != Some(&hash)) else {
+            return Ok(());
+        };
+        if dry_run {
+            return Ok(());
+        }
+
+        let mut patch = json!({"metadata": {"annotations": {KUBECONFIG_HASH_ANNOTATION: hash}}});
+        if rotated {
+            patch["spec"] = json!({"redeployAgentGeneration": generation});
+        }
+        api.patch(
+            &existing.name_any(),
+            &PatchParams::default(),
+            &Patch::Merge(patch),
+        )
+        .await
+        .map_err(BundleError::KubeconfigRotation)?;
+
+        if rotated {
+            let note = format!(
+                "Kubeconfig secret `{secret_name}` changed, redeploying Fleet agent for `{}` with generation {}",
+                existing.name_any(),
+                generation.unwrap_or_default()
+            );
+            self.publish(ctx, EventType::Normal, "AgentRedeploy", note)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Resolve a pre-existing Fleet cluster with the same name, not managed for this cluster,
+    /// according to the adoption policy. Returns `false` when the import should not proceed.
+    async fn adopt(
+        &self,
+        ctx: Arc<Context>,
+        fleet: &mut fleet_cluster::Cluster,
+        config: &ClusterConfig,
+        dry_run: bool,
+    ) -> BundleResult<bool> {
+        let namespace = self.namespace().unwrap_or_default();
+        let api: Api<fleet_cluster::Cluster> = Api::namespaced(ctx.client.clone(), &namespace);
+        let Some(existing) = api
+            .get_opt(&fleet.name_any())
+            .await
+            .map_err(BundleError::Adoption)?
+        else {
+            self.resolve_adoption(ctx, dry_run).await?;
+            return Ok(true);
+        };
+
+        if self.owns_fleet_cluster(&existing) {
+            // Fleet clusters imported before the cluster name label was introduced get the label
+            if !dry_run && !existing.labels().contains_key(CLUSTER_NAME_LABEL) {
+                self.label_fleet_cluster(&api, &existing.name_any()).await?;
+            }
+            self.resolve_adoption(ctx, dry_run).await?;
+            return Ok(true);
+        }
+
+        let name = existing.name_any();
+        match config.adoption_policy() {
+            AdoptionPolicy::Adopt => {
+                if !dry_run {
+                    self.label_fleet_cluster(&api, &name).await?;
+                    let note = format!("Adopted existing Fleet cluster `{name}`");
+                    self.publish(ctx.clone(), EventType::Normal, "FleetClusterAdopted", note)
+                        .await?;
+                }
+                self.resolve_adoption(ctx, dry_run).await?;
+                Ok(true)
+            }
+            AdoptionPolicy::Rename => {
+                let renamed = unique_name(&name, &format!("{namespace}/{}", self.name_any()));
+                let taken = api
+                    .get_metadata_opt(&renamed)
+                    .await
+                    .map_err(BundleError::Adoption)?;
+                match taken {
+                    Some(taken) if !self.owns(&taken) => {
+                        let note = format!(
+                            "Fleet clusters `{name}` and `{renamed}` are not managed for this cluster"
+                        );
+                        self.refuse_adoption(ctx, note, dry_run).await?;
+                        Ok(false)
+                    }
+                    taken => {
+                        if taken.is_none() {
+                            let note = format!(
+                                "Fleet cluster `{name}` already exists, importing as `{renamed}`"
+                            );
+                            self.publish(
+                                ctx.clone(),
+                                EventType::Normal,
+                                "FleetClusterRenamed",
+                                note,
+                            )
+                            .await?;
+                        }
+                        fleet.metadata.name = Some(renamed);
+                        self.resolve_adoption(ctx, dry_run).await?;
+                        Ok(true)
+                    }
+                }
+            }
+            AdoptionPolicy::Refuse => {
+                let note = format!(
+                    "Fleet cluster `{name}` already exists and is not managed for this cluster"
+                );
+                self.refuse_adoption(ctx, note, dry_run).await?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Set the cluster name label on the Fleet cluster, marking it as managed for this cluster.
+    async fn label_fleet_cluster(
+        &self,
+        api: &Api<fleet_cluster::Cluster>,
+        name: &str,
+    ) -> BundleResult<()> {
+        api.patch(
+            name,
+            &PatchParams::default(),
+            &Patch::Merge(json!({
+                "metadata": {"labels": {CLUSTER_NAME_LABEL: self.name_any()}}
+            })),
+        )
+        .await
+        .map_err(BundleError::Adoption)?;
+        Ok(())
+    }
+
+    async fn refuse_adoption(
+        &self,
+        ctx: Arc<Context>,
+        note: String,
+        dry_run: bool,
+    ) -> BundleResult<()> {
+        self.warn(ctx.clone(), "AdoptionRefused", note.clone())
+            .await?;
+        if dry_run {
+            return Ok(());
+        }
+
+        let condition = ClusterCondition {
+            type_: ADOPTION_CONDITION.into(),
+            status: ConditionStatus::False,
+            reason: "Refused".into(),
+            message: note,
+        };
+        set_cluster_condition(
+            ctx,
+            &self.namespace().unwrap_or_default(),
+            &self.name_any(),
+            &condition,
+        )
+        .await
+        .map_err(BundleError::Adoption)?;
+        Ok(())
+    }
+
+    /// Clear a previously reported adoption conflict.
+    async fn resolve_adoption(&self, ctx: Arc<Context>, dry_run: bool) -> BundleResult<()> {
+        let reported = self
+            .status
+            .as_ref()
+            .is_some_and(|status| status.has_condition(ADOPTION_CONDITION));
+        if !reported || dry_run {
+            return Ok(());
+        }
+
+        let condition = ClusterCondition {
+            type_: ADOPTION_CONDITION.into(),
+            status: ConditionStatus::True,
+            reason: "Managed".into(),
+            message: "Fleet cluster is managed for this cluster".into(),
+        };
+        set_cluster_condition(
+            ctx,
+            &self.namespace().unwrap_or_default(),
+            &self.name_any(),
+            &condition,
+        )
+        .await
+        .map_err(BundleError::Adoption)?;
+        Ok(())
+    }
+
+    /// Check if the Fleet cluster name is already taken by a Fleet cluster imported for a different CAPI cluster.
+    async fn name_collision(
+        &self,
+        ctx: Arc<Context>,
+        fleet: &fleet_cluster::Cluster,
+    ) -> BundleResult<Option<String>> {
+        let api: Api<fleet_cluster::Cluster> =
+            Api::namespaced(ctx.client.clone(), &self.namespace().unwrap_or_default());
+        let existing = api
+            .get_opt(&fleet.name_any())
+            .await
+            .map_err(BundleError::NameLookup)?;
+
+        Ok(existing.and_then(|existing| {
+            let owner = existing.labels().get(CLUSTER_NAME_LABEL)?;
+            (*owner != self.name_any()).then(|| {
+                format!(
+                    "Fleet cluster `{}` is already imported for cluster `{owner}`",
+                    fleet.name_any()
+                )
+            })
+        }))
+    }
+
+    /// Check if the ClusterGroup name is already taken by a group for a different ClusterClass.
+    async fn group_collision(
+        &self,
+        ctx: Arc<Context>,
+        group: &ClusterGroup,
+    ) -> BundleResult<Option<String>> {
+        let api: Api<ClusterGroup> =
+            Api::namespaced(ctx.client.clone(), &self.namespace().unwrap_or_default());
+        let existing = api
+            .get_opt(&group.name_any())
+            .await
+            .map_err(BundleError::NameLookup)?;
+
+        Ok(existing.and_then(|existing| {
+            let class = existing.cluster_class_name()?;
+            let class_namespace = existing.cluster_class_namespace()?;
+            (group.cluster_class_name() != Some(class.clone())
+                || group.cluster_class_namespace() != Some(class_namespace.clone()))
+            .then(|| {
+                format!(
+                    "ClusterGroup `{}` is already created for class `{class}` in `{class_namespace}`",
+                    group.name_any()
+                )
+            })
+        }))
+    }
 
-        ready_condition.or(cp_ready).map(|_| self)
+    /// Report an import problem in the cluster condition, publishing a warning event only when the
+    /// condition changes. A resolved condition is updated only if it was reported before. The condition
+    /// is not written in dry-run mode, so the warning is published on every plan.
+    async fn report_condition(
+        &self,
+        ctx: Arc<Context>,
+        condition: &ClusterCondition,
+        dry_run: bool,
+    ) -> BundleResult<()> {
+        let reported = self
+            .status
+            .as_ref()
+            .is_some_and(|status| status.has_condition(&condition.type_));
+        if condition.is_true() && (!reported || dry_run) {
+            return Ok(());
+        }
+
+        let changed = match dry_run {
+            true => true,
+            false => set_cluster_condition(
+                ctx.clone(),
+                &self.namespace().unwrap_or_default(),
+                &self.name_any(),
+                condition,
+            )
+            .await
+            .map_err(BundleError::Condition)?,
+        };
+        if changed && !condition.is_true() {
+            self.warn(ctx, &condition.reason, condition.message.clone())
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Publish a warning event on the cluster.
+    async fn warn(&self, ctx: Arc<Context>, reason: &str, note: String) -> BundleResult<()> {
+        self.publish(ctx, EventType::Warning, reason, note).await
+    }
+
+    async fn publish(
+        &self,
+        ctx: Arc<Context>,
+        type_: EventType,
+        reason: &str,
+        note: String,
+    ) -> BundleResult<()> {
+        match ctx
+            .diagnostics
+            .read()
+            .await
+            .recorder(ctx.client.clone())
+            .publish(
+                &Event {
+                    type_,
+                    reason: reason.into(),
+                    note: Some(note),
+                    action: "Importing".into(),
+                    secondary: None,
+                },
+                &self.object_ref(&()),
+            )
+            .await
+        {
+            // Ignore forbidden errors on event creation
+            Err(kube::Error::Api(e)) if &e.reason == "Forbidden" => Ok(()),
+            e => e.map_err(BundleError::Event),
+        }
+    }
+
+    pub fn cluster_ready(&self) -> Option<&Self> {
+        self.status.as_ref()?.control_plane_ready().then_some(self)
     }
 
     pub async fn add_namespace_dynamic_watch(
@@ -251,4 +1210,1074 @@ impl Cluster {
 
         Ok(Action::await_change())
     }
+
+    /// De-import a previously imported cluster, which no longer matches any of the import profiles,
+    /// or when cluster import is disabled. Fleet resources are handled according to the de-import policy,
+    /// and the finalizer is released.
+    pub async fn deimport(
+        cluster: Arc<PartialObjectMeta<Cluster>>,
+        ctx: Arc<Context>,
+    ) -> crate::Result<Action> {
+        Ok(deimport_cluster(&cluster, ctx).await?)
+    }
+}
+
+async fn deimport_cluster(
+    cluster: &PartialObjectMeta<Cluster>,
+    ctx: Arc<Context>,
+) -> DeimportResult<Action> {
+    let imported = cluster.finalizers().iter().any(|f| f == FLEET_FINALIZER);
+    if !imported || cluster.metadata.deletion_timestamp.is_some() {
+        return Ok(Action::await_change());
+    }
+
+    // Imported clusters are removed while Fleet is being uninstalled
+    let config = ctx.config().await?;
+    if config.uninstalling() {
+        return Ok(Action::await_change());
+    }
+
+    let name = cluster.name_any();
+    let ns = cluster.namespace().unwrap_or_default();
+    if config.cluster_operations_enabled() {
+        let namespace: Namespace = Api::all(ctx.client.clone())
+            .get(&ns)
+            .await
+            .map_err(LabelCheckError::from)?;
+        if config
+            .import_profile(cluster.labels(), namespace.labels())
+            .map_err(LabelCheckError::from)?
+            .is_some()
+        {
+            return Ok(Action::await_change());
+        }
+    }
+
+    let dry_run = ctx.dry_run || config.dry_run_enabled();
+    if config.deimport_policy() == DeimportPolicy::Delete {
+        let plan_owner = dry_run.then(|| cluster.object_ref(&()));
+        delete_fleet_resources(ctx.clone(), &name, &ns, plan_owner.as_ref())
+            .await
+            .map_err(DeimportError::Cleanup)?;
+        if !dry_run {
+            remove_service_account_kubeconfig(ctx.clone(), &ns, &name).await?;
+        }
+    }
+
+    if dry_run {
+        return Ok(Action::await_change());
+    }
+
+    let finalizers: Vec<_> = cluster
+        .finalizers()
+        .iter()
+        .filter(|f| *f != FLEET_FINALIZER)
+        .collect();
+    Api::<Cluster>::namespaced(ctx.client.clone(), &ns)
+        .patch(
+            &name,
+            &PatchParams::default(),
+            &Patch::Merge(json!({
+                "metadata": {
+                    "resourceVersion": cluster.resource_version(),
+                    "finalizers": finalizers,
+                }
+            })),
+        )
+        .await
+        .map_err(DeimportError::Finalizer)?;
+
+    info!("Cluster left the import scope, released finalizer");
+    match ctx
+        .diagnostics
+        .read()
+        .await
+        .recorder(ctx.client.clone())
+        .publish(
+            &Event {
+                type_: EventType::Normal,
+                reason: "Deimported".into(),
+                note: Some(format!(
+                    "Cluster no longer matches the import selectors, Fleet resources are {}",
+                    match config.deimport_policy() {
+                        DeimportPolicy::Delete => "removed",
+                        DeimportPolicy::Orphan => "orphaned",
+                    }
+                )),
+                action: "Deimporting".into(),
+                secondary: None,
+            },
+            &cluster.object_ref(&()),
+        )
+        .await
+    {
+        // Ignore forbidden errors on event creation
+        Err(kube::Error::Api(e)) if &e.reason == "Forbidden" => Ok(Action::await_change()),
+        e => e
+            .map(|_| Action::await_change())
+            .map_err(DeimportError::Event),
+    }
+}
+
+/// Remove Fleet clusters created for the CAPI cluster, along with the ClusterGroup and
+/// BundleNamespaceMapping no longer referenced by the remaining Fleet clusters in the namespace.
+async fn delete_fleet_resources(
+    ctx: Arc<Context>,
+    name: &str,
+    ns: &str,
+    plan_owner: Option<&ObjectReference>,
+) -> kube::Result<()> {
+    let fleet_api = Api::<fleet_cluster::Cluster>::namespaced(ctx.client.clone(), ns);
+    let (imported, others): (Vec<_>, Vec<_>) = fleet_api
+        .list(&ListParams::default())
+        .await?
+        .into_iter()
+        .partition(|c| c.capi_cluster_name().as_deref() == Some(name));
+
+    for fleet in imported {
+        delete_if_exists(ctx.clone(), &fleet_api, &fleet.name_any(), plan_owner).await?;
+
+        let labels = fleet.labels();
+        let label_used =
+            |key: &str, value: &String| others.iter().any(|c| c.labels().get(key) == Some(value));
+        let groups = Api::<ClusterGroup>::namespaced(ctx.client.clone(), ns);
+
+        for label in [
+            INFRASTRUCTURE_KIND_LABEL,
+            CONTROL_PLANE_KIND_LABEL,
+            KUBERNETES_MINOR_VERSION_LABEL,
+        ] {
+            if let Some(value) = labels.get(label).filter(|value| !label_used(label, value)) {
+                let group = match label == KUBERNETES_MINOR_VERSION_LABEL {
+                    true => version_group_name(value),
+                    false => provider_group_name(label, value),
+                };
+                delete_if_exists(ctx.clone(), &groups, &group, plan_owner).await?;
+            }
+        }
+
+        let (Some(class), Some(class_ns)) = (
+            labels.get(CLUSTER_CLASS_LABEL),
+            labels.get(CLUSTER_CLASS_NAMESPACE_LABEL),
+        ) else {
+            continue;
+        };
+
+        let class_used = others.iter().any(|c| {
+            c.labels().get(CLUSTER_CLASS_LABEL) == Some(class)
+                && c.labels().get(CLUSTER_CLASS_NAMESPACE_LABEL) == Some(class_ns)
+        });
+        if !class_used {
+            let group = class_group_name(class, class_ns);
+            delete_if_exists(ctx.clone(), &groups, &group, plan_owner).await?;
+        }
+
+        if class_ns != ns && !label_used(CLUSTER_CLASS_NAMESPACE_LABEL, class_ns) {
+            let mappings = Api::<BundleNamespaceMapping>::namespaced(ctx.client.clone(), class_ns);
+            delete_if_exists(ctx.clone(), &mappings, ns, plan_owner).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Kubernetes minor version label of the existing Fleet cluster, if it differs from the desired one.
+async fn changed_version(
+    ctx: Arc<Context>,
+    fleet: &fleet_cluster::Cluster,
+) -> kube::Result<Option<String>> {
+    let existing = Api::<fleet_cluster::Cluster>::namespaced(
+        ctx.client.clone(),
+        &fleet.namespace().unwrap_or_default(),
+    )
+    .get_metadata_opt(&fleet.name_any())
+    .await?;
+    let desired = fleet.labels().get(KUBERNETES_MINOR_VERSION_LABEL);
+    Ok(existing
+        .and_then(|existing| {
+            existing
+                .labels()
+                .get(KUBERNETES_MINOR_VERSION_LABEL)
+                .cloned()
+        })
+        .filter(|previous| desired != Some(previous)))
+}
+
+/// Delete the Kubernetes version group once no Fleet cluster in the namespace has the version.
+async fn delete_unused_version_group(
+    ctx: Arc<Context>,
+    ns: &str,
+    version: &str,
+) -> kube::Result<()> {
+    let selector =
+        ListParams::default().labels(&format!("{KUBERNETES_MINOR_VERSION_LABEL}={version}"));
+    let used = !Api::<fleet_cluster::Cluster>::namespaced(ctx.client.clone(), ns)
+        .list_metadata(&selector.limit(1))
+        .await?
+        .items
+        .is_empty();
+    if used {
+        return Ok(());
+    }
+
+    let name = version_group_name(version);
+    info!("Removing stale Kubernetes version group {name}");
+    let groups = Api::<ClusterGroup>::namespaced(ctx.client.clone(), ns);
+    delete_if_exists(ctx, &groups, &name, None).await
+}
+
+/// Delete the resource if it exists. With the plan owner set, the deletion is only reported on it.
+async fn delete_if_exists<R>(
+    ctx: Arc<Context>,
+    api: &Api<R>,
+    name: &str,
+    plan_owner: Option<&ObjectReference>,
+) -> kube::Result<()>
+where
+    R: Clone + Serialize + DeserializeOwned + std::fmt::Debug,
+    R: kube::Resource<DynamicType = (), Scope = NamespaceResourceScope>,
+{
+    let Some(res) = api.get_opt(name).await? else {
+        return Ok(());
+    };
+
+    if let Some(owner) = plan_owner {
+        return plan_delete(ctx, &res, owner).await;
+    }
+
+    match api.delete(name, &Default::default()).await {
+        Err(kube::Error::Api(e)) if e.code == 404 => Ok(()),
+        r => r.map(|_| ()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use http::Method;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+    use kube::core::PartialObjectMeta;
+    use kube::runtime::controller::Action;
+    use kube::{Resource as _, ResourceExt as _};
+    use serde_json::{json, Value};
+
+    use crate::api::capi_cluster::{
+        Cluster, ClusterCondition, ConditionStatus, AGENT_CONFIG_MAP_ANNOTATION, CLUSTER_NAME_LABEL,
+    };
+    use crate::api::fleet_addon_config::{
+        AdoptionPolicy, ClusterConfig, Drain, FleetAddonConfig, ReadinessGate,
+    };
+    use crate::api::fleet_cluster;
+    use crate::api::fleet_clustergroup::{
+        CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL, INFRASTRUCTURE_KIND_LABEL,
+        KUBERNETES_MINOR_VERSION_LABEL,
+    };
+    use crate::controllers::addon_config::FLEET_UNINSTALL_FINALIZER;
+    use crate::controllers::controller::{FleetController as _, FLEET_FINALIZER};
+    use crate::controllers::kubeconfig::{
+        ADMIN_KUBECONFIG_HASH_ANNOTATION, KUBECONFIG_HASH_ANNOTATION,
+    };
+    use crate::fixtures::{list, mock_context, with_config, ApiRequest};
+
+    use super::{
+        changed_version, deimport_cluster, delete_fleet_resources, delete_unused_version_group,
+        AGENT_OVERRIDES_CONDITION, DELIVERY_CONDITION, DRAIN_CHECK_INTERVAL, DRAIN_CONDITION,
+        NAME_CONDITION,
+    };
+
+    const FLEET_CLUSTERS_PATH: &str = "/apis/fleet.cattle.io/v1alpha1/namespaces/default/clusters";
+    const GROUPS_PATH: &str = "/apis/fleet.cattle.io/v1alpha1/namespaces/default/clustergroups";
+    const MAPPING_PATH: &str =
+        "/apis/fleet.cattle.io/v1alpha1/namespaces/classes/bundlenamespacemappings/default";
+    const CLUSTER_PATH: &str = "/apis/cluster.x-k8s.io/v1beta1/namespaces/default/clusters/cluster";
+
+    fn cluster() -> Cluster {
+        serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default", "uid": "uid"},
+            "spec": {},
+        }))
+        .unwrap()
+    }
+
+    fn fleet_cluster(name: &str, labels: Value) -> Value {
+        json!({
+            "apiVersion": "fleet.cattle.io/v1alpha1",
+            "kind": "Cluster",
+            "metadata": {"name": name, "namespace": "default", "labels": labels},
+            "spec": {},
+        })
+    }
+
+    fn imported_cluster() -> PartialObjectMeta<Cluster> {
+        serde_json::from_value(json!({
+            "apiVersion": "meta.k8s.io/v1",
+            "kind": "PartialObjectMetadata",
+            "metadata": {
+                "name": "cluster",
+                "namespace": "default",
+                "resourceVersion": "1",
+                "finalizers": [FLEET_FINALIZER],
+            },
+        }))
+        .unwrap()
+    }
+
+    fn config(deimport_policy: &str) -> Value {
+        json!({
+            "apiVersion": "addons.cluster.x-k8s.io/v1alpha1",
+            "kind": "FleetAddonConfig",
+            "metadata": {"name": "fleet-addon-config"},
+            "spec": {
+                "cluster": {
+                    "namespaceSelector": {"matchLabels": {"import": "true"}},
+                    "selector": {"matchLabels": {"import": "true"}},
+                },
+                "deimportPolicy": deimport_policy,
+            },
+        })
+    }
+
+    /// Fleet clusters in the namespace, with the imported cluster sharing the provider group
+    /// with another cluster, and being the only one of its ClusterClass.
+    fn fleet_resources(request: &ApiRequest) -> Option<Value> {
+        let imported = fleet_cluster(
+            "cluster",
+            json!({
+                CLUSTER_NAME_LABEL: "cluster",
+                CLUSTER_CLASS_LABEL: "quick-start",
+                CLUSTER_CLASS_NAMESPACE_LABEL: "classes",
+                INFRASTRUCTURE_KIND_LABEL: "AWSCluster",
+            }),
+        );
+        let other = fleet_cluster(
+            "other",
+            json!({
+                CLUSTER_NAME_LABEL: "other",
+                INFRASTRUCTURE_KIND_LABEL: "AWSCluster",
+            }),
+        );
+
+        let group = |name: &str| {
+            json!({
+                "apiVersion": "fleet.cattle.io/v1alpha1",
+                "kind": "ClusterGroup",
+                "metadata": {"name": name, "namespace": "default"},
+                "spec": {},
+            })
+        };
+
+        match (&request.method, request.path.as_str()) {
+            (&Method::GET, FLEET_CLUSTERS_PATH) => Some(list(vec![imported, other])),
+            (_, path) if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => Some(imported),
+            (_, path) if path.starts_with(GROUPS_PATH) => {
+                Some(group(path.rsplit('/').next().unwrap()))
+            }
+            (_, MAPPING_PATH) => Some(json!({
+                "apiVersion": "fleet.cattle.io/v1alpha1",
+                "kind": "BundleNamespaceMapping",
+                "metadata": {"name": "default", "namespace": "classes"},
+                "bundleSelector": {},
+                "namespaceSelector": {},
+            })),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changed_version() {
+        let (ctx, _) = mock_context(|request| {
+            (request.path == format!("{FLEET_CLUSTERS_PATH}/cluster"))
+                .then(|| fleet_cluster("cluster", json!({KUBERNETES_MINOR_VERSION_LABEL: "v1.30"})))
+        });
+
+        for (desired, changed) in [("v1.30", None), ("v1.31", Some("v1.30".to_string()))] {
+            let fleet: fleet_cluster::Cluster = serde_json::from_value(fleet_cluster(
+                "cluster",
+                json!({KUBERNETES_MINOR_VERSION_LABEL: desired}),
+            ))
+            .unwrap();
+            assert_eq!(changed_version(ctx.clone(), &fleet).await.unwrap(), changed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_unused_version_group() {
+        let group = format!("{GROUPS_PATH}/kubernetes-v1.30");
+        for (clusters, deleted) in [(vec![fleet_cluster("other", json!({}))], 0), (vec![], 1)] {
+            let served = group.clone();
+            let (ctx, server) = mock_context(move |request| match request.path.as_str() {
+                FLEET_CLUSTERS_PATH => Some(list(clusters.clone())),
+                path if path == served => Some(json!({
+                    "apiVersion": "fleet.cattle.io/v1alpha1",
+                    "kind": "ClusterGroup",
+                    "metadata": {"name": "kubernetes-v1.30", "namespace": "default"},
+                    "spec": {},
+                })),
+                _ => None,
+            });
+
+            // Version group is kept while another Fleet cluster has the version
+            delete_unused_version_group(ctx, "default", "v1.30")
+                .await
+                .unwrap();
+            assert_eq!(server.requests(Method::DELETE, &group).len(), deleted);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delete_fleet_resources() {
+        let (ctx, server) = mock_context(fleet_resources);
+        delete_fleet_resources(ctx, "cluster", "default", None)
+            .await
+            .unwrap();
+
+        let deleted = |path: &str| server.requests(Method::DELETE, path).len();
+        assert_eq!(deleted(&format!("{FLEET_CLUSTERS_PATH}/cluster")), 1);
+        assert_eq!(deleted(&format!("{FLEET_CLUSTERS_PATH}/other")), 0);
+        assert_eq!(deleted(&format!("{GROUPS_PATH}/quick-start.classes")), 1);
+        assert_eq!(deleted(MAPPING_PATH), 1);
+
+        // Provider group still selects the other cluster
+        assert_eq!(
+            deleted(&format!("{GROUPS_PATH}/infrastructure-awscluster")),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_fleet_resources_plan() {
+        let (ctx, server) = mock_context(fleet_resources);
+        let owner = cluster().object_ref(&());
+        delete_fleet_resources(ctx, "cluster", "default", Some(&owner))
+            .await
+            .unwrap();
+
+        // Deletions are only reported on the CAPI cluster
+        assert!(server
+            .requests(Method::DELETE, &format!("{FLEET_CLUSTERS_PATH}/cluster"))
+            .is_empty());
+        assert_eq!(server.events().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_deimport_cluster() {
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            "/api/v1/namespaces/default" => Some(json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": {"name": "default"},
+            })),
+            CLUSTER_PATH => Some(json!({
+                "apiVersion": "cluster.x-k8s.io/v1beta1",
+                "kind": "Cluster",
+                "metadata": {"name": "cluster", "namespace": "default"},
+                "spec": {},
+            })),
+            _ => fleet_resources(request),
+        });
+
+        let ctx = with_config(ctx, config("Delete"));
+        let action = deimport_cluster(&imported_cluster(), ctx).await.unwrap();
+        assert_eq!(action, Action::await_change());
+
+        let deleted = server.requests(Method::DELETE, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+        assert_eq!(deleted.len(), 1);
+
+        let patch = server.requests(Method::PATCH, CLUSTER_PATH);
+        assert_eq!(patch.len(), 1);
+        assert_eq!(patch[0].body["metadata"]["finalizers"], json!([]));
+        assert_eq!(server.events(), vec!["Deimported"]);
+    }
+
+    #[tokio::test]
+    async fn test_deimport_cluster_orphan() {
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            "/api/v1/namespaces/default" => Some(json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": {"name": "default"},
+            })),
+            CLUSTER_PATH => Some(json!({
+                "apiVersion": "cluster.x-k8s.io/v1beta1",
+                "kind": "Cluster",
+                "metadata": {"name": "cluster", "namespace": "default"},
+                "spec": {},
+            })),
+            _ => None,
+        });
+
+        let ctx = with_config(ctx, config("Orphan"));
+        deimport_cluster(&imported_cluster(), ctx).await.unwrap();
+
+        // Fleet resources are kept, while the finalizer is released
+        assert!(server.requests(Method::GET, FLEET_CLUSTERS_PATH).is_empty());
+        assert_eq!(server.requests(Method::PATCH, CLUSTER_PATH).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deimport_cluster_in_scope() {
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            "/api/v1/namespaces/default" => Some(json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": {"name": "default", "labels": {"import": "true"}},
+            })),
+            _ => None,
+        });
+
+        let ctx = with_config(ctx, config("Delete"));
+        deimport_cluster(&imported_cluster(), ctx).await.unwrap();
+
+        // Clusters matching an import profile stay imported
+        assert!(server.requests(Method::GET, FLEET_CLUSTERS_PATH).is_empty());
+        assert!(server.requests(Method::PATCH, CLUSTER_PATH).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_drains_unready_cluster() {
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            "/api/v1/namespaces/default" => Some(json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": {"name": "default", "labels": {"import": "true"}},
+            })),
+            "/apis/fleet.cattle.io/v1alpha1/bundledeployments" => Some(list(vec![])),
+            path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => Some(fleet_cluster(
+                "cluster",
+                json!({CLUSTER_NAME_LABEL: "cluster"}),
+            )),
+            _ => None,
+        });
+
+        let mut config: FleetAddonConfig = serde_json::from_value(config("Orphan")).unwrap();
+        let cluster_config = config.spec.cluster.as_mut().unwrap();
+        cluster_config.readiness = Some(ReadinessGate {
+            min_ready_workers: Some(1),
+            ..Default::default()
+        });
+        cluster_config.drain = Some(Drain::default());
+
+        let mut cluster = cluster();
+        cluster.metadata.deletion_timestamp = Some(Time(Utc::now()));
+        cluster.metadata.finalizers = Some(vec![FLEET_FINALIZER.into()]);
+
+        // Import gates are skipped on deletion, so the workloads are drained
+        cluster.cleanup(ctx, Some(&config)).await.unwrap();
+        let deleted = server.requests(Method::DELETE, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(server.events(), vec!["Drained"]);
+
+        // Readiness is neither checked nor reported
+        let machines = "/apis/cluster.x-k8s.io/v1beta1/namespaces/default/machines";
+        assert!(server.requests(Method::GET, machines).is_empty());
+        assert!(server.requests(Method::PATCH, CLUSTER_PATH).is_empty());
+    }
+
+    /// Drain config and a deleted cluster, with the drain condition if the drain is in progress.
+    fn draining_cluster(draining: bool) -> (FleetAddonConfig, Value) {
+        let mut config: FleetAddonConfig = serde_json::from_value(config("Orphan")).unwrap();
+        config.spec.cluster.as_mut().unwrap().drain = Some(Drain::default());
+
+        let condition = ClusterCondition {
+            type_: DRAIN_CONDITION.into(),
+            status: ConditionStatus::False,
+            reason: "Draining".into(),
+            message: "Waiting for Fleet cluster cluster BundleDeployments removal".into(),
+        };
+        let time = "2025-01-01T00:00:00Z";
+        let (conditions, v1beta2_conditions) = match draining {
+            true => (
+                vec![condition.to_v1beta1(time)],
+                vec![condition.to_v1beta2(time, None)],
+            ),
+            false => (vec![], vec![]),
+        };
+        let cluster = json!({
+            "apiVersion": "cluster.x-k8s.io/v1beta1",
+            "kind": "Cluster",
+            "metadata": {
+                "name": "cluster",
+                "namespace": "default",
+                "uid": "uid",
+                "deletionTimestamp": Time(Utc::now()),
+                "finalizers": [FLEET_FINALIZER],
+            },
+            "spec": {},
+            "status": {
+                "conditions": conditions,
+                "v1beta2": {"conditions": v1beta2_conditions},
+            },
+        });
+        (config, cluster)
+    }
+
+    fn drain_resources(
+        request: &ApiRequest,
+        cluster: &Value,
+        fleet: Option<Value>,
+        deployments: Vec<Value>,
+    ) -> Option<Value> {
+        match request.path.as_str() {
+            "/api/v1/namespaces/default" => Some(json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": {"name": "default", "labels": {"import": "true"}},
+            })),
+            "/apis/fleet.cattle.io/v1alpha1/bundledeployments" => Some(list(deployments)),
+            FLEET_CLUSTERS_PATH => Some(list(vec![])),
+            path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => fleet,
+            path if path.starts_with(CLUSTER_PATH) => Some(cluster.clone()),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_drain_pending() {
+        let deployment = json!({
+            "apiVersion": "fleet.cattle.io/v1alpha1",
+            "kind": "BundleDeployment",
+            "metadata": {"name": "bundle", "namespace": "cluster-default-cluster"},
+        });
+        let fleet = fleet_cluster("cluster", json!({CLUSTER_NAME_LABEL: "cluster"}));
+        for (draining, events) in [(false, vec!["Draining"]), (true, vec![])] {
+            let (config, cluster) = draining_cluster(draining);
+            let (fleet, deployment, served) = (fleet.clone(), deployment.clone(), cluster.clone());
+            let (ctx, server) = mock_context(move |request| {
+                drain_resources(
+                    request,
+                    &served,
+                    Some(fleet.clone()),
+                    vec![deployment.clone()],
+                )
+            });
+
+            // Pending drain is requeued, reporting the transition only once
+            let cluster: Cluster = serde_json::from_value(cluster).unwrap();
+            let action = cluster.cleanup(ctx, Some(&config)).await.unwrap();
+            assert_eq!(action, Action::requeue(DRAIN_CHECK_INTERVAL));
+            assert_eq!(server.events(), events);
+
+            let patches = server.requests(Method::PATCH, &format!("{CLUSTER_PATH}/status"));
+            assert_eq!(patches.len(), usize::from(!draining));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_drain_done() {
+        // Drained is reported only when the Fleet cluster was deleted or the drain was in progress
+        let fleet = fleet_cluster("cluster", json!({CLUSTER_NAME_LABEL: "cluster"}));
+        for (fleet, draining, events) in [
+            (None, false, vec![]),
+            (None, true, vec!["Drained"]),
+            (Some(fleet), false, vec!["Drained"]),
+        ] {
+            let (config, cluster) = draining_cluster(draining);
+            let served = cluster.clone();
+            let (ctx, server) = mock_context(move |request| {
+                drain_resources(request, &served, fleet.clone(), vec![])
+            });
+
+            let cluster: Cluster = serde_json::from_value(cluster).unwrap();
+            let action = cluster.cleanup(ctx, Some(&config)).await.unwrap();
+            assert_eq!(action, Action::await_change());
+            assert_eq!(server.events(), events);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_skips_unmanaged_fleet_cluster() {
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            "/api/v1/namespaces/default" => Some(json!({
+                "apiVersion": "v1",
+                "kind": "Namespace",
+                "metadata": {"name": "default", "labels": {"import": "true"}},
+            })),
+            FLEET_CLUSTERS_PATH => Some(list(vec![])),
+            path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => Some(fleet_cluster(
+                "cluster",
+                json!({CLUSTER_NAME_LABEL: "other"}),
+            )),
+            _ => None,
+        });
+
+        let mut config: FleetAddonConfig = serde_json::from_value(config("Orphan")).unwrap();
+        config.spec.cluster.as_mut().unwrap().drain = Some(Drain::default());
+
+        let mut cluster = cluster();
+        cluster.metadata.deletion_timestamp = Some(Time(Utc::now()));
+
+        // Fleet cluster imported for a different CAPI cluster is not drained
+        cluster.cleanup(ctx, Some(&config)).await.unwrap();
+        let deleted = server.requests(Method::DELETE, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+        assert!(deleted.is_empty());
+        assert!(!server.events().contains(&"Drained".to_string()));
+    }
+
+    fn unmanaged_fleet_cluster(request: &ApiRequest) -> Option<Value> {
+        match request.path.as_str() {
+            path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => {
+                Some(fleet_cluster("cluster", json!({})))
+            }
+            path if path.starts_with(CLUSTER_PATH) => Some(json!({
+                "apiVersion": "cluster.x-k8s.io/v1beta1",
+                "kind": "Cluster",
+                "metadata": {"name": "cluster", "namespace": "default", "resourceVersion": "1"},
+                "spec": {},
+            })),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_adopt() {
+        let (ctx, server) = mock_context(unmanaged_fleet_cluster);
+        let config = ClusterConfig {
+            adoption_policy: Some(AdoptionPolicy::Adopt),
+            ..Default::default()
+        };
+        let mut fleet = cluster().to_cluster(Some(&config)).unwrap();
+
+        let adopted = cluster().adopt(ctx, &mut fleet, &config, false).await;
+        assert!(adopted.unwrap());
+
+        let patch = server.requests(Method::PATCH, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+        assert_eq!(patch.len(), 1);
+        assert_eq!(
+            patch[0].body["metadata"]["labels"][CLUSTER_NAME_LABEL],
+            "cluster"
+        );
+        assert_eq!(server.events(), vec!["FleetClusterAdopted"]);
+    }
+
+    #[tokio::test]
+    async fn test_adopt_dry_run() {
+        let (ctx, server) = mock_context(unmanaged_fleet_cluster);
+        let config = ClusterConfig {
+            adoption_policy: Some(AdoptionPolicy::Adopt),
+            ..Default::default()
+        };
+        let mut fleet = cluster().to_cluster(Some(&config)).unwrap();
+
+        // Adoption is only reported once the Fleet cluster is labelled
+        let adopted = cluster().adopt(ctx, &mut fleet, &config, true).await;
+        assert!(adopted.unwrap());
+        assert!(server
+            .requests(Method::PATCH, &format!("{FLEET_CLUSTERS_PATH}/cluster"))
+            .is_empty());
+        assert!(server.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_adopt_legacy_fleet_cluster() {
+        // Fleet cluster imported before the cluster name label was introduced
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => Some(json!({
+                "apiVersion": "fleet.cattle.io/v1alpha1",
+                "kind": "Cluster",
+                "metadata": {"name": "cluster", "namespace": "default"},
+                "spec": {"kubeConfigSecret": "cluster-kubeconfig"},
+            })),
+            _ => None,
+        });
+        let config = ClusterConfig::default();
+        let mut fleet = cluster().to_cluster(Some(&config)).unwrap();
+
+        // Legacy cluster is managed without the adoption, and gets the label backfilled
+        let adopted = cluster().adopt(ctx, &mut fleet, &config, false).await;
+        assert!(adopted.unwrap());
+
+        let patch = server.requests(Method::PATCH, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+        assert_eq!(patch.len(), 1);
+        assert_eq!(
+            patch[0].body["metadata"]["labels"][CLUSTER_NAME_LABEL],
+            "cluster"
+        );
+        assert!(server.events().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_adopt_refused() {
+        let (ctx, server) = mock_context(unmanaged_fleet_cluster);
+
+        // Existing Fleet clusters are not taken over by default
+        let config = ClusterConfig::default();
+        assert_eq!(config.adoption_policy(), AdoptionPolicy::Refuse);
+        let mut fleet = cluster().to_cluster(Some(&config)).unwrap();
+
+        let adopted = cluster().adopt(ctx, &mut fleet, &config, false).await;
+        assert!(!adopted.unwrap());
+
+        let patch = server.requests(Method::PATCH, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+        assert!(patch.is_empty());
+        assert_eq!(server.events(), vec!["AdoptionRefused"]);
+
+        let status = server.requests(Method::PATCH, &format!("{CLUSTER_PATH}/status"));
+        assert_eq!(status.len(), 1);
+        let condition = &status[0].body["status"]["conditions"][0];
+        assert_eq!(condition["type"], "FleetClusterAdoption");
+        assert_eq!(condition["status"], "False");
+    }
+
+    #[tokio::test]
+    async fn test_kubeconfig_rotation_service_account() {
+        // Token renewals keep the admin hash, while an admin kubeconfig change records a new one
+        for (admin_hash, redeploy) in [("admin-before", false), ("admin-after", true)] {
+            let secret = json!({
+                "apiVersion": "v1",
+                "kind": "Secret",
+                "metadata": {
+                    "name": "cluster-fleet-kubeconfig",
+                    "namespace": "default",
+                    "annotations": {ADMIN_KUBECONFIG_HASH_ANNOTATION: admin_hash},
+                },
+                "data": {"value": "c2NvcGVk"},
+            });
+            let (ctx, server) = mock_context(move |request| match request.path.as_str() {
+                "/api/v1/namespaces/default/secrets/cluster-fleet-kubeconfig" => {
+                    Some(secret.clone())
+                }
+                path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => Some(json!({
+                    "apiVersion": "fleet.cattle.io/v1alpha1",
+                    "kind": "Cluster",
+                    "metadata": {
+                        "name": "cluster",
+                        "namespace": "default",
+                        "labels": {CLUSTER_NAME_LABEL: "cluster"},
+                        "annotations": {KUBECONFIG_HASH_ANNOTATION: "admin-before"},
+                    },
+                    "spec": {"redeployAgentGeneration": 1},
+                })),
+                _ => None,
+            });
+            let config = ClusterConfig {
+                service_account_kubeconfig: Some(Default::default()),
+                ..Default::default()
+            };
+            let mut fleet = cluster().to_cluster(Some(&config)).unwrap();
+
+            cluster()
+                .kubeconfig_rotation(ctx, &mut fleet, &config, false)
+                .await
+                .unwrap();
+            assert_eq!(
+                fleet.annotations().get(KUBECONFIG_HASH_ANNOTATION).unwrap(),
+                admin_hash
+            );
+
+            let patch = server.requests(Method::PATCH, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+            assert_eq!(patch.len(), usize::from(redeploy));
+            if redeploy {
+                assert_eq!(patch[0].body["spec"]["redeployAgentGeneration"], 2);
+                assert_eq!(server.events(), vec!["AgentRedeploy"]);
+            } else {
+                assert_eq!(fleet.spec.redeploy_agent_generation, Some(1));
+                assert!(server.events().is_empty());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pause_fleet_cluster() {
+        let paused_condition = ClusterCondition {
+            type_: DELIVERY_CONDITION.into(),
+            status: ConditionStatus::False,
+            reason: "Paused".into(),
+            message: "Fleet delivery is paused with the cluster".into(),
+        };
+        let time = "2025-01-01T00:00:00Z";
+        for (conditions, transition) in [
+            (vec![], true),
+            (vec![paused_condition.to_v1beta1(time)], false),
+        ] {
+            let cluster = json!({
+                "apiVersion": "cluster.x-k8s.io/v1beta1",
+                "kind": "Cluster",
+                "metadata": {"name": "cluster", "namespace": "default", "uid": "uid"},
+                "spec": {"paused": true},
+                "status": {"conditions": conditions},
+            });
+            let served = cluster.clone();
+            let (ctx, server) = mock_context(move |request| match request.path.as_str() {
+                FLEET_CLUSTERS_PATH => Some(list(vec![fleet_cluster(
+                    "cluster",
+                    json!({CLUSTER_NAME_LABEL: "cluster"}),
+                )])),
+                path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => Some(fleet_cluster(
+                    "cluster",
+                    json!({CLUSTER_NAME_LABEL: "cluster"}),
+                )),
+                path if path.starts_with(CLUSTER_PATH) => Some(served.clone()),
+                _ => None,
+            });
+
+            // Fleet clusters are only looked up and patched on the pause transition
+            let cluster: Cluster = serde_json::from_value(cluster).unwrap();
+            cluster.pause_fleet_cluster(ctx).await.unwrap();
+            let count = usize::from(transition);
+            assert_eq!(
+                server.requests(Method::GET, FLEET_CLUSTERS_PATH).len(),
+                count
+            );
+            let patches = server.requests(Method::PATCH, &format!("{FLEET_CLUSTERS_PATH}/cluster"));
+            assert_eq!(patches.len(), count);
+            let status = server.requests(Method::PATCH, &format!("{CLUSTER_PATH}/status"));
+            assert_eq!(status.len(), count);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_agent_overrides_reported_once() {
+        let invalid = ClusterCondition {
+            type_: AGENT_OVERRIDES_CONDITION.into(),
+            status: ConditionStatus::False,
+            reason: "InvalidAgentOverride".into(),
+            message: "agent overrides ConfigMap `missing` not found".into(),
+        };
+        let time = "2025-01-01T00:00:00Z";
+        for (config_map, reported, events, patches) in [
+            (Some("missing"), false, vec!["InvalidAgentOverride"], 1),
+            (Some("missing"), true, vec![], 0),
+            (None, true, vec![], 1),
+            (None, false, vec![], 0),
+        ] {
+            let conditions = match reported {
+                true => vec![invalid.to_v1beta1(time)],
+                false => vec![],
+            };
+            let v1beta2_conditions = match reported {
+                true => vec![invalid.to_v1beta2(time, None)],
+                false => vec![],
+            };
+            let annotations = match config_map {
+                Some(name) => json!({AGENT_CONFIG_MAP_ANNOTATION: name}),
+                None => json!({}),
+            };
+            let cluster = json!({
+                "apiVersion": "cluster.x-k8s.io/v1beta1",
+                "kind": "Cluster",
+                "metadata": {
+                    "name": "cluster",
+                    "namespace": "default",
+                    "resourceVersion": "1",
+                    "annotations": annotations,
+                },
+                "spec": {},
+                "status": {
+                    "conditions": conditions,
+                    "v1beta2": {"conditions": v1beta2_conditions},
+                },
+            });
+            let served = cluster.clone();
+            let (ctx, server) = mock_context(move |request| match request.path.as_str() {
+                path if path.starts_with(CLUSTER_PATH) => Some(served.clone()),
+                _ => None,
+            });
+
+            // Warning is published on the transition, and the resolution is recorded once reported
+            let cluster: Cluster = serde_json::from_value(cluster).unwrap();
+            cluster.agent_overrides(ctx, false).await.unwrap();
+            assert_eq!(server.events(), events);
+
+            let status = server.requests(Method::PATCH, &format!("{CLUSTER_PATH}/status"));
+            assert_eq!(status.len(), patches);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_name_collision_reported_once() {
+        let collision = ClusterCondition {
+            type_: NAME_CONDITION.into(),
+            status: ConditionStatus::False,
+            reason: "NameCollision".into(),
+            message: "Fleet cluster `cluster` is already imported for cluster `other`".into(),
+        };
+        let time = "2025-01-01T00:00:00Z";
+        let config: FleetAddonConfig = serde_json::from_value(json!({
+            "metadata": {"name": "fleet-addon-config"},
+            "spec": {"cluster": {"namespaceSelector": {}, "selector": {}}},
+        }))
+        .unwrap();
+        for (reported, events, patches) in [(false, vec!["NameCollision"], 1), (true, vec![], 0)] {
+            let (conditions, v1beta2_conditions) = match reported {
+                true => (
+                    vec![collision.to_v1beta1(time)],
+                    vec![collision.to_v1beta2(time, None)],
+                ),
+                false => (vec![], vec![]),
+            };
+            let cluster = json!({
+                "apiVersion": "cluster.x-k8s.io/v1beta1",
+                "kind": "Cluster",
+                "metadata": {"name": "cluster", "namespace": "default", "resourceVersion": "1"},
+                "spec": {},
+                "status": {
+                    "controlPlaneReady": true,
+                    "conditions": conditions,
+                    "v1beta2": {"conditions": v1beta2_conditions},
+                },
+            });
+            let served = cluster.clone();
+            let (ctx, server) = mock_context(move |request| match request.path.as_str() {
+                "/api/v1/namespaces/default" => Some(json!({
+                    "apiVersion": "v1",
+                    "kind": "Namespace",
+                    "metadata": {"name": "default"},
+                })),
+                path if path == format!("{FLEET_CLUSTERS_PATH}/cluster") => Some(fleet_cluster(
+                    "cluster",
+                    json!({CLUSTER_NAME_LABEL: "other"}),
+                )),
+                path if path.starts_with(CLUSTER_PATH) => Some(served.clone()),
+                _ => None,
+            });
+
+            // Collision is not imported, with the warning published only on the transition
+            let cluster: Cluster = serde_json::from_value(cluster).unwrap();
+            let bundle = cluster.to_bundle(ctx, Some(&config)).await.unwrap();
+            assert!(bundle.is_none());
+            assert_eq!(server.events(), events);
+
+            let status = server.requests(Method::PATCH, &format!("{CLUSTER_PATH}/status"));
+            assert_eq!(status.len(), patches);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_to_bundle_uninstalling() {
+        let deleted = |finalizers: Value| -> FleetAddonConfig {
+            serde_json::from_value(json!({
+                "metadata": {
+                    "name": "fleet-addon-config",
+                    "deletionTimestamp": "2025-01-01T00:00:00Z",
+                    "finalizers": finalizers,
+                },
+                "spec": {"cluster": {"namespaceSelector": {}, "selector": {}}},
+            }))
+            .unwrap()
+        };
+
+        // Clusters are not re-imported while Fleet is being uninstalled
+        let (ctx, server) = mock_context(|_| None);
+        let uninstalling = deleted(json!([FLEET_UNINSTALL_FINALIZER]));
+        assert!(cluster()
+            .to_bundle(ctx, Some(&uninstalling))
+            .await
+            .unwrap()
+            .is_none());
+        assert!(server
+            .requests(Method::GET, "/api/v1/namespaces/default")
+            .is_empty());
+
+        // Without the config, or without the uninstall, the import proceeds with the default settings
+        for config in [None, Some(deleted(json!(["other"])))] {
+            let (ctx, server) = mock_context(|_| None);
+            let _ = cluster().to_bundle(ctx, config.as_ref()).await;
+            assert_eq!(
+                server
+                    .requests(Method::GET, "/api/v1/namespaces/default")
+                    .len(),
+                1
+            );
+        }
+    }
 }
@@ -5,23 +5,32 @@ use crate::api::fleet_clustergroup::ClusterGroup;
 
 use kube::api::PatchParams;
 
+use k8s_openapi::api::core::v1::ObjectReference;
 use kube::runtime::controller::Action;
+use kube::{Resource as _, ResourceExt as _};
 
 use std::sync::Arc;
 
-use super::controller::{
-    fetch_config, get_or_create, patch, Context, FleetBundle, FleetController,
-};
+use super::controller::{get_or_create, patch, plan, Context, FleetBundle, FleetController};
 use super::{BundleResult, GroupSyncResult};
 
 pub struct FleetClusterClassBundle {
+    class: ObjectReference,
     fleet_group: ClusterGroup,
     config: FleetAddonConfig,
+    dry_run: bool,
 }
 
 impl FleetBundle for FleetClusterClassBundle {
     #[allow(refining_impl_trait)]
     async fn sync(&mut self, ctx: Arc<Context>) -> GroupSyncResult<Action> {
+        if self.dry_run {
+            let pp = PatchParams::apply("addon-provider-fleet");
+            let pp = self.config.cluster_class_patch_enabled().then_some(&pp);
+            plan(ctx, &mut self.fleet_group, pp, &self.class).await?;
+            return Ok(Action::await_change());
+        }
+
         match self.config.cluster_class_patch_enabled() {
             true => {
                 patch(
@@ -41,13 +50,22 @@ impl FleetBundle for FleetClusterClassBundle {
 impl FleetController for ClusterClass {
     type Bundle = FleetClusterClassBundle;
 
-    async fn to_bundle(&self, ctx: Arc<Context>) -> BundleResult<Option<FleetClusterClassBundle>> {
-        let config = fetch_config(ctx.client.clone()).await?;
+    async fn to_bundle(
+        &self,
+        ctx: Arc<Context>,
+        config: Option<&FleetAddonConfig>,
+    ) -> BundleResult<Option<FleetClusterClassBundle>> {
+        let config = config.cloned().unwrap_or_default();
         if !config.cluster_class_operations_enabled() {
             return Ok(None);
         }
 
         let mut fleet_group: ClusterGroup = self.into();
+        let inherited = config.group_label_sync().apply(self.labels());
+        fleet_group
+            .labels_mut()
+            .retain(|key, _| !self.labels().contains_key(key));
+        fleet_group.labels_mut().extend(inherited);
         if let Some(ClusterClassConfig {
             set_owner_references: Some(true),
             ..
@@ -58,7 +76,9 @@ impl FleetController for ClusterClass {
         }
 
         Ok(Some(FleetClusterClassBundle {
+            class: self.object_ref(&()),
             fleet_group,
+            dry_run: ctx.dry_run || config.dry_run_enabled(),
             config,
         }))
     }
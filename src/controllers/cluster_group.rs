@@ -1,4 +1,6 @@
-use crate::api::fleet_clustergroup::ClusterGroup;
+use crate::api::fleet_clustergroup::{
+    ClusterGroup, CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL,
+};
 
 use cluster_api_rs::capi_clusterclass::ClusterClass;
 use kube::api::{Patch, PatchParams};
@@ -6,10 +8,11 @@ use kube::runtime::controller::Action;
 use kube::{Api, ResourceExt};
 use serde_json::json;
 
+use std::collections::BTreeMap;
 use std::ops::Deref;
 use std::sync::Arc;
 
-use super::controller::{patch, Context, FLEET_FINALIZER};
+use super::controller::{patch, plan, Context, FLEET_FINALIZER};
 use super::{GroupSyncResult, SyncError};
 
 impl ClusterGroup {
@@ -19,21 +22,32 @@ impl ClusterGroup {
     }
 
     async fn sync(&mut self, ctx: Arc<Context>) -> GroupSyncResult<Action> {
+        let config = ctx.config().await?;
+        let dry_run = ctx.dry_run || config.dry_run_enabled();
         if let Some(cc_ref) = self.cluster_class_ref() {
             let class = ctx.client.fetch::<ClusterClass>(&cc_ref).await?;
-            self.labels_mut().extend(
-                class
-                    .labels()
-                    .iter()
-                    .map(|(k, v)| (k.to_string(), v.to_string())),
-            );
-
-            patch(
-                ctx.clone(),
-                self,
-                &PatchParams::apply("addon-provider-fleet"),
-            )
-            .await?;
+
+            // Labels are rebuilt from the source, so the field manager drops the excluded ones
+            let mut labels: BTreeMap<String, String> = self
+                .labels()
+                .iter()
+                .filter(|(key, _)| {
+                    [CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL].contains(&key.as_str())
+                })
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            labels.extend(config.group_label_sync().apply(class.labels()));
+            *self.labels_mut() = labels;
+
+            let pp = PatchParams::apply("addon-provider-fleet");
+            match dry_run {
+                true => plan(ctx.clone(), self, Some(&pp), &cc_ref).await?,
+                false => patch(ctx.clone(), self, &pp).await?,
+            };
+        }
+
+        if dry_run {
+            return Ok(Action::await_change());
         }
 
         if self.finalizers().iter().any(|f| f == FLEET_FINALIZER) {
@@ -51,3 +65,82 @@ impl ClusterGroup {
         Ok(Action::await_change())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use http::Method;
+    use serde_json::json;
+
+    use crate::api::fleet_clustergroup::{
+        ClusterGroup, CLUSTER_CLASS_LABEL, CLUSTER_CLASS_NAMESPACE_LABEL,
+    };
+    use crate::fixtures::{mock_context, with_config};
+
+    const GROUP_PATH: &str =
+        "/apis/fleet.cattle.io/v1alpha1/namespaces/default/clustergroups/quick-start";
+
+    #[tokio::test]
+    async fn test_sync_drops_excluded_labels() {
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            "/apis/cluster.x-k8s.io/v1beta1/namespaces/default/clusterclasses/quick-start" => {
+                Some(json!({
+                    "apiVersion": "cluster.x-k8s.io/v1beta1",
+                    "kind": "ClusterClass",
+                    "metadata": {
+                        "name": "quick-start",
+                        "namespace": "default",
+                        "labels": {"team": "a", "internal": "true"},
+                    },
+                    "spec": {},
+                }))
+            }
+            GROUP_PATH => Some(request.body.clone()),
+            _ => None,
+        });
+        let ctx = with_config(
+            ctx,
+            json!({
+                "apiVersion": "addons.cluster.x-k8s.io/v1alpha1",
+                "kind": "FleetAddonConfig",
+                "metadata": {"name": "fleet-addon-config"},
+                "spec": {
+                    "cluster": {
+                        "namespaceSelector": {},
+                        "selector": {},
+                        "labelSync": {"exclude": ["internal"]},
+                    },
+                },
+            }),
+        );
+
+        // Label synced before the exclude rule was added
+        let group: ClusterGroup = serde_json::from_value(json!({
+            "metadata": {
+                "name": "quick-start",
+                "namespace": "default",
+                "labels": {
+                    CLUSTER_CLASS_LABEL: "quick-start",
+                    CLUSTER_CLASS_NAMESPACE_LABEL: "default",
+                    "team": "a",
+                    "internal": "true",
+                },
+            },
+            "spec": {},
+        }))
+        .unwrap();
+        Arc::new(group).reconcile(ctx).await.unwrap();
+
+        let patch = server.requests(Method::PATCH, GROUP_PATH);
+        assert_eq!(patch.len(), 1);
+        assert_eq!(
+            patch[0].body["metadata"]["labels"],
+            json!({
+                CLUSTER_CLASS_LABEL: "quick-start",
+                CLUSTER_CLASS_NAMESPACE_LABEL: "default",
+                "team": "a",
+            })
+        );
+    }
+}
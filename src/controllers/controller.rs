@@ -1,3 +1,4 @@
+use crate::api::capi_cluster::{Cluster, ClusterCondition};
 use crate::api::fleet_addon_config::FleetAddonConfig;
 use crate::controllers::PatchError;
 use crate::metrics::Diagnostics;
@@ -7,27 +8,32 @@ use chrono::Utc;
 
 use futures::stream::SelectAll;
 use futures::Stream;
+use k8s_openapi::api::core::v1::ObjectReference;
 use k8s_openapi::NamespaceResourceScope;
 
-use kube::api::{DynamicObject, Patch, PatchParams, PostParams};
+use kube::api::{ApiResource, DynamicObject, Patch, PatchParams, PostParams};
 
 use kube::runtime::events::{Event, EventType};
+use kube::runtime::reflector::{ObjectRef, Store};
 use kube::runtime::{finalizer, watcher};
 
 use kube::{api::Api, client::Client, runtime::controller::Action};
 
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use serde_json::json;
 use tracing::field::display;
 
 use std::fmt::Debug;
+use std::ops::Deref;
 use std::pin::Pin;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::{self, debug, info, instrument, Span};
 
 use super::{
-    BundleResult, ConfigFetchResult, GetOrCreateError, GetOrCreateResult, PatchResult, SyncError,
+    BundleError, BundleResult, ConfigFetchResult, GetOrCreateError, GetOrCreateResult, PatchResult,
+    SyncError,
 };
 
 pub static FLEET_FINALIZER: &str = "fleet.addons.cluster.x-k8s.io";
@@ -51,6 +57,26 @@ pub struct Context {
     pub stream: BroadcastStream<DynamicStream>,
     // k8s minor version
     pub version: u32,
+    // plan changes to fleet resources without applying them
+    pub dry_run: bool,
+    // FleetAddonConfig reflector store
+    pub config: Store<FleetAddonConfig>,
+}
+
+impl Context {
+    /// FleetAddonConfig from the reflector store, or the default config when it is missing.
+    pub(crate) async fn config(&self) -> ConfigFetchResult<FleetAddonConfig> {
+        Ok(self.config_opt().await?.unwrap_or_default())
+    }
+
+    /// FleetAddonConfig from the reflector store, waiting for the initial list to complete.
+    pub(crate) async fn config_opt(&self) -> ConfigFetchResult<Option<FleetAddonConfig>> {
+        self.config.wait_until_ready().await?;
+        Ok(self
+            .config
+            .get(&ObjectRef::new("fleet-addon-config"))
+            .map(|config| config.deref().clone()))
+    }
 }
 
 #[instrument(skip_all, fields(name = res.name_any(), namespace = res.namespace(), api_version = typed_gvk::<R>(()).api_version(), kind = R::kind(&()).to_string()), err)]
@@ -159,6 +185,180 @@ where
     Ok(Action::await_change())
 }
 
+/// Preview a create or patch of the resource without persisting it. Existing resources are applied
+/// with server-side apply in dry-run mode, and only resources which would change are reported
+/// on the owner, as the planned resource may not exist yet.
+/// Without patch params, the resource is only planned for creation, following `get_or_create`.
+#[instrument(skip_all, fields(name = res.name_any(), namespace = res.namespace(), api_version = typed_gvk::<R>(()).api_version(), kind = R::kind(&()).to_string()), err)]
+pub(crate) async fn plan<R>(
+    ctx: Arc<Context>,
+    res: &mut R,
+    pp: Option<&PatchParams>,
+    owner: &ObjectReference,
+) -> PatchResult<Action>
+where
+    R: Clone + Serialize + DeserializeOwned + Debug,
+    R: kube::Resource<DynamicType = (), Scope = NamespaceResourceScope>,
+    R: kube::ResourceExt,
+{
+    let ns = res.namespace().unwrap_or(String::from("default"));
+    let api: Api<R> = Api::namespaced(ctx.client.clone(), &ns);
+
+    res.meta_mut().managed_fields = None;
+
+    let existing = api
+        .get_opt(&res.name_any())
+        .await
+        .map_err(PatchError::Patch)?;
+    let (reason, action) = match (existing, pp) {
+        (None, _) => ("DryRunCreate", "create"),
+        (Some(_), None) => return Ok(Action::await_change()),
+        (Some(existing), Some(pp)) => {
+            let mut pp = pp.clone();
+            pp.dry_run = true;
+            let applied = api
+                .patch(&res.name_any(), &pp, &Patch::Apply(&res))
+                .await
+                .map_err(PatchError::Patch)?;
+
+            if comparable(&existing)? == comparable(&applied)? {
+                return Ok(Action::await_change());
+            }
+
+            ("DryRunPatch", "patch")
+        }
+    };
+
+    info!("Would {action} object");
+    publish_plan(ctx, reason, action, res, owner).await?;
+
+    Ok(Action::await_change())
+}
+
+/// Preview deletion of the resource, reporting it on the owner if the resource exists.
+pub(crate) async fn plan_delete<R>(
+    ctx: Arc<Context>,
+    res: &R,
+    owner: &ObjectReference,
+) -> kube::Result<()>
+where
+    R: Clone + Serialize + DeserializeOwned + Debug,
+    R: kube::Resource<DynamicType = (), Scope = NamespaceResourceScope>,
+    R: kube::ResourceExt,
+{
+    let ns = res.namespace().unwrap_or(String::from("default"));
+    let api: Api<R> = Api::namespaced(ctx.client.clone(), &ns);
+    if api.get_opt(&res.name_any()).await?.is_none() {
+        return Ok(());
+    }
+
+    info!("Would delete object {}", res.name_any());
+    publish_plan(ctx, "DryRunDelete", "delete", res, owner).await
+}
+
+/// Object representation without server populated fields, used to detect planned changes.
+fn comparable(res: &impl Serialize) -> serde_json::Result<serde_json::Value> {
+    let mut value = serde_json::to_value(res)?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove("status");
+    }
+    if let Some(metadata) = value.get_mut("metadata").and_then(|m| m.as_object_mut()) {
+        for field in ["managedFields", "resourceVersion", "generation"] {
+            metadata.remove(field);
+        }
+    }
+
+    Ok(value)
+}
+
+async fn publish_plan<R>(
+    ctx: Arc<Context>,
+    reason: &str,
+    action: &str,
+    res: &R,
+    owner: &ObjectReference,
+) -> kube::Result<()>
+where
+    R: kube::Resource<DynamicType = ()> + kube::ResourceExt,
+{
+    match ctx
+        .diagnostics
+        .read()
+        .await
+        .recorder(ctx.client.clone())
+        // Record planned change
+        .publish(
+            &Event {
+                type_: EventType::Normal,
+                reason: reason.into(),
+                note: Some(format!(
+                    "Would {action} fleet {} `{}` in `{}`",
+                    R::kind(&()),
+                    res.name_any(),
+                    res.namespace().unwrap_or_default()
+                )),
+                action: "Planning".into(),
+                secondary: Some(res.object_ref(&())),
+            },
+            owner,
+        )
+        .await
+    {
+        // Ignore forbidden errors on event creation
+        Err(kube::Error::Api(e)) if &e.reason == "Forbidden" => Ok(()),
+        e => e,
+    }
+}
+
+/// Set the condition on the CAPI Cluster status, in both v1beta1 and v1beta2 condition lists.
+/// Returns true if the cluster status was updated.
+pub(crate) async fn set_cluster_condition(
+    ctx: Arc<Context>,
+    namespace: &str,
+    name: &str,
+    condition: &ClusterCondition,
+) -> kube::Result<bool> {
+    let api: Api<DynamicObject> = Api::namespaced_with(
+        ctx.client.clone(),
+        namespace,
+        &ApiResource::erase::<Cluster>(&()),
+    );
+    let cluster = api.get(name).await?;
+    let generation = cluster.metadata.generation;
+
+    // Conditions are handled as raw values to preserve fields unknown to the typed API, like severity
+    let status = &cluster.data["status"];
+    let mut conditions = status["conditions"].as_array().cloned().unwrap_or_default();
+    let mut v1beta2_conditions = status["v1beta2"]["conditions"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+
+    let updated = condition.upsert(&mut conditions, |time| condition.to_v1beta1(time));
+    let updated_v1beta2 = condition.upsert(&mut v1beta2_conditions, |time| {
+        condition.to_v1beta2(time, generation)
+    });
+    if !updated && !updated_v1beta2 {
+        return Ok(false);
+    }
+
+    // Resource version guards the condition lists from concurrent updates by CAPI controllers
+    api.patch_status(
+        name,
+        &PatchParams::default(),
+        &Patch::Merge(json!({
+            "metadata": {"resourceVersion": cluster.metadata.resource_version},
+            "status": {
+                "conditions": conditions,
+                "v1beta2": {"conditions": v1beta2_conditions},
+            },
+        })),
+    )
+    .await?;
+
+    Ok(true)
+}
+
 pub(crate) async fn fetch_config(client: Client) -> ConfigFetchResult<FleetAddonConfig> {
     Ok(Api::all(client)
         .get_opt("fleet-addon-config")
@@ -192,9 +392,36 @@ where
         let api = Api::namespaced(ctx.client.clone(), namespace.as_str());
         debug!("Reconciling");
 
+        let config = ctx.config_opt().await.map_err(BundleError::from)?;
+        let dry_run = ctx.dry_run || config.as_ref().is_some_and(|c| c.dry_run_enabled());
+        let finalized = self.finalizers().iter().any(|f| f == FLEET_FINALIZER);
+        let deleted = self.meta().deletion_timestamp.is_some();
+
+        // Resources which were never imported are left untouched while the import is deferred
+        if !finalized && !deleted && self.import_deferred() {
+            return Ok(Action::await_change());
+        }
+
+        // Finalizer is not added in dry-run mode, as the resource would be mutated.
+        // An existing finalizer is still released on deletion, so the deletion is not blocked.
+        if dry_run && !(deleted && finalized) {
+            return match deleted {
+                true => self.cleanup(ctx, config.as_ref()).await,
+                false => match self.to_bundle(ctx.clone(), config.as_ref()).await? {
+                    Some(mut bundle) => bundle
+                        .sync(ctx)
+                        .await
+                        .map_err(Into::into)
+                        .map_err(Into::into),
+                    _ => Ok(Action::await_change()),
+                },
+            };
+        }
+
+        let config = config.as_ref();
         finalizer(&api, FLEET_FINALIZER, self, |event| async {
             match event {
-                finalizer::Event::Apply(c) => match c.to_bundle(ctx.clone()).await? {
+                finalizer::Event::Apply(c) => match c.to_bundle(ctx.clone(), config).await? {
                     Some(mut bundle) => bundle
                         .sync(ctx)
                         .await
@@ -202,15 +429,19 @@ where
                         .map_err(Into::into),
                     _ => Ok(Action::await_change()),
                 },
-                finalizer::Event::Cleanup(c) => c.cleanup(ctx).await,
+                finalizer::Event::Cleanup(c) => c.cleanup(ctx, config).await,
             }
         })
         .await
         .map_err(|e| Error::FinalizerError(Box::new(e)))
     }
 
-    async fn cleanup(&self, ctx: Arc<Context>) -> crate::Result<Action> {
-        if let Some(mut bundle) = self.to_bundle(ctx.clone()).await? {
+    async fn cleanup(
+        &self,
+        ctx: Arc<Context>,
+        config: Option<&FleetAddonConfig>,
+    ) -> crate::Result<Action> {
+        if let Some(mut bundle) = self.to_bundle(ctx.clone(), config).await? {
             return Ok(bundle.cleanup(ctx).await?);
         }
 
@@ -240,5 +471,208 @@ where
         Ok(Action::await_change())
     }
 
-    async fn to_bundle(&self, ctx: Arc<Context>) -> BundleResult<Option<Self::Bundle>>;
+    /// Build the bundle of Fleet resources from the FleetAddonConfig, `None` if the config is missing.
+    async fn to_bundle(
+        &self,
+        ctx: Arc<Context>,
+        config: Option<&FleetAddonConfig>,
+    ) -> BundleResult<Option<Self::Bundle>>;
+
+    /// Check if the import is deferred, skipping the finalizer for resources not imported yet.
+    fn import_deferred(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use http::Method;
+    use k8s_openapi::api::core::v1::ObjectReference;
+    use kube::api::PatchParams;
+    use kube::runtime::controller::Action;
+    use serde_json::{json, Value};
+
+    use crate::api::capi_cluster::Cluster;
+    use crate::api::fleet_clustergroup::ClusterGroup;
+    use crate::fixtures::{mock_context, with_config};
+
+    use super::{comparable, plan, plan_delete, FleetController as _, FLEET_FINALIZER};
+
+    static GROUP_PATH: &str =
+        "/apis/fleet.cattle.io/v1alpha1/namespaces/default/clustergroups/group";
+    static CLUSTER_PATH: &str =
+        "/apis/cluster.x-k8s.io/v1beta1/namespaces/default/clusters/cluster";
+
+    fn group(labels: Value) -> Value {
+        json!({
+            "apiVersion": "fleet.cattle.io/v1alpha1",
+            "kind": "ClusterGroup",
+            "metadata": {
+                "name": "group",
+                "namespace": "default",
+                "labels": labels,
+                "resourceVersion": "1",
+                "generation": 1,
+                "managedFields": [{"manager": "addon-provider-fleet"}],
+            },
+            "spec": {},
+            "status": {"clusterCount": 1},
+        })
+    }
+
+    fn owner() -> ObjectReference {
+        ObjectReference {
+            api_version: Some("cluster.x-k8s.io/v1beta1".into()),
+            kind: Some("Cluster".into()),
+            name: Some("cluster".into()),
+            namespace: Some("default".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_comparable() {
+        let mut applied = group(json!({"a": "b"}));
+        applied["metadata"]["resourceVersion"] = "2".into();
+        applied["metadata"]["generation"] = 2.into();
+        applied["metadata"]["managedFields"] = json!([]);
+        applied["status"] = json!({"clusterCount": 2});
+        assert_eq!(
+            comparable(&group(json!({"a": "b"}))).unwrap(),
+            comparable(&applied).unwrap()
+        );
+
+        assert_ne!(
+            comparable(&group(json!({"a": "b"}))).unwrap(),
+            comparable(&group(json!({"a": "c"}))).unwrap()
+        );
+        assert!(comparable(&group(json!({})))
+            .unwrap()
+            .get("status")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plan_create() {
+        let (ctx, server) = mock_context(|_| None);
+        let mut res: ClusterGroup = serde_json::from_value(group(json!({}))).unwrap();
+
+        let pp = PatchParams::apply("addon-provider-fleet");
+        let action = plan(ctx, &mut res, Some(&pp), &owner()).await.unwrap();
+        assert_eq!(action, Action::await_change());
+
+        // Nothing is applied, the event is published on the owner
+        assert!(server.requests(Method::PATCH, GROUP_PATH).is_empty());
+        let events = server.requests(
+            Method::POST,
+            "/apis/events.k8s.io/v1/namespaces/default/events",
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].body["reason"], "DryRunCreate");
+        assert_eq!(events[0].body["regarding"]["kind"], "Cluster");
+        assert_eq!(events[0].body["related"]["kind"], "ClusterGroup");
+    }
+
+    #[tokio::test]
+    async fn test_plan_patch() {
+        // Server-side apply in dry-run mode returns the changed labels
+        let (ctx, server) = mock_context(|request| match request.method {
+            Method::GET => Some(group(json!({"a": "b"}))),
+            Method::PATCH => Some(group(request.body["metadata"]["labels"].clone())),
+            _ => None,
+        });
+
+        let pp = PatchParams::apply("addon-provider-fleet");
+        let mut unchanged: ClusterGroup = serde_json::from_value(group(json!({"a": "b"}))).unwrap();
+        plan(ctx.clone(), &mut unchanged, Some(&pp), &owner())
+            .await
+            .unwrap();
+        assert!(server.events().is_empty());
+
+        let mut changed: ClusterGroup = serde_json::from_value(group(json!({"a": "c"}))).unwrap();
+        plan(ctx, &mut changed, Some(&pp), &owner()).await.unwrap();
+        assert_eq!(server.events(), ["DryRunPatch"]);
+
+        let patches = server.requests(Method::PATCH, GROUP_PATH);
+        assert_eq!(patches.len(), 2);
+        assert!(patches.iter().all(|p| p.query.contains("dryRun=All")));
+    }
+
+    #[tokio::test]
+    async fn test_plan_delete() {
+        let (ctx, server) =
+            mock_context(|request| (request.path == GROUP_PATH).then(|| group(json!({}))));
+
+        let res: ClusterGroup = serde_json::from_value(group(json!({}))).unwrap();
+        plan_delete(ctx, &res, &owner()).await.unwrap();
+        assert_eq!(server.events(), ["DryRunDelete"]);
+        assert!(server.requests(Method::DELETE, GROUP_PATH).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_paused_not_imported() {
+        let (ctx, server) = mock_context(|_| None);
+        let ctx = with_config(
+            ctx,
+            json!({
+                "apiVersion": "addons.cluster.x-k8s.io/v1alpha1",
+                "kind": "FleetAddonConfig",
+                "metadata": {"name": "fleet-addon-config"},
+                "spec": {},
+            }),
+        );
+        let cluster: Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default", "uid": "uid"},
+            "spec": {"paused": true},
+        }))
+        .unwrap();
+
+        // Paused cluster is neither finalized nor looked up until it is unpaused
+        let action = Arc::new(cluster).reconcile(ctx).await.unwrap();
+        assert_eq!(action, Action::await_change());
+        assert!(server.requests(Method::PATCH, CLUSTER_PATH).is_empty());
+        assert!(server
+            .requests(Method::GET, "/api/v1/namespaces/default")
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_dry_run_releases_finalizer() {
+        let cluster = json!({
+            "apiVersion": "cluster.x-k8s.io/v1beta1",
+            "kind": "Cluster",
+            "metadata": {
+                "name": "cluster",
+                "namespace": "default",
+                "uid": "uid",
+                "deletionTimestamp": "2025-01-01T00:00:00Z",
+                "finalizers": [FLEET_FINALIZER],
+            },
+            "spec": {},
+        });
+        // Config being deleted skips the import, leaving only the finalizer release
+        let response = cluster.clone();
+        let (ctx, server) = mock_context(move |request| match request.path.as_str() {
+            path if path == CLUSTER_PATH => Some(response.clone()),
+            _ => None,
+        });
+        let ctx = with_config(
+            ctx,
+            json!({
+                "apiVersion": "addons.cluster.x-k8s.io/v1alpha1",
+                "kind": "FleetAddonConfig",
+                "metadata": {"name": "fleet-addon-config", "deletionTimestamp": "2025-01-01T00:00:00Z"},
+                "spec": {"dryRun": true},
+            }),
+        );
+
+        let cluster: Cluster = serde_json::from_value(cluster).unwrap();
+        Arc::new(cluster).reconcile(ctx).await.unwrap();
+
+        let patches = server.requests(Method::PATCH, CLUSTER_PATH);
+        assert_eq!(patches.len(), 1);
+        assert!(patches[0].body.to_string().contains(FLEET_FINALIZER));
+    }
 }
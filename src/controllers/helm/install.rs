@@ -1,18 +1,30 @@
-use std::{fmt::Display, process::Stdio};
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
 
+use semver::{Version, VersionReq};
 use serde::Deserialize;
-use tokio::process::{Child, Command};
+use serde_json::Value;
+use sha2::{Digest as _, Sha256};
+use tokio::{
+    io::AsyncWriteExt as _,
+    process::{Child, Command},
+};
 
 use crate::api::fleet_addon_config::{FeatureGates, Install};
 
 use super::{
-    FleetCRDInstallResult, FleetInstallResult, MetadataGetResult, RepoAddResult, RepoSearchResult,
-    RepoUpdateResult,
+    FleetCRDInstallResult, FleetInstallResult, FleetRollbackResult, FleetUninstallResult,
+    MetadataGetResult, RepoAddResult, RepoSearchError, RepoSearchResult, RepoUpdateResult,
 };
 
 #[derive(Default, Clone)]
 pub struct FleetChart {
-    pub repo: String,
+    pub repo: HelmRepository,
     pub version: Option<Install>,
     pub namespace: String,
 
@@ -23,6 +35,82 @@ pub struct FleetChart {
     pub bootstrap_local_cluster: bool,
 
     pub feature_gates: FeatureGates,
+
+    pub values: Option<Value>,
+    pub crd_values: Option<Value>,
+    /// Reset the release values on upgrade when no values are set, as previously set values were removed
+    pub reset_values: bool,
+}
+
+/// HelmRepository holds resolved chart repository settings, shared by all helm invocations.
+#[derive(Default, Clone)]
+pub struct HelmRepository {
+    pub name: String,
+    pub url: String,
+    pub credentials: Option<RepositoryCredentials>,
+    pub ca_file: Option<Arc<TempFile>>,
+    pub insecure_skip_tls_verify: bool,
+}
+
+/// TempFile is removed once the last reference to it is dropped, so secret material
+/// passed to helm by path does not outlive the reconcile.
+#[derive(Debug)]
+pub struct TempFile(PathBuf);
+
+impl TempFile {
+    pub fn write(path: PathBuf, contents: impl AsRef<[u8]>) -> io::Result<Self> {
+        fs::write(&path, contents)?;
+        Ok(Self(path))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[derive(Clone)]
+pub struct RepositoryCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl HelmRepository {
+    pub fn is_oci(&self) -> bool {
+        self.url.starts_with("oci://")
+    }
+
+    /// Chart reference used for lookup and install operations.
+    pub fn chart(&self, chart: &str) -> String {
+        match self.is_oci() {
+            true => format!("{}/{chart}", self.url.trim_end_matches('/')),
+            false => format!("{}/{chart}", self.name),
+        }
+    }
+
+    fn registry_host(&self) -> &str {
+        self.url
+            .trim_start_matches("oci://")
+            .split('/')
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// TLS settings for commands communicating with the repository server.
+    fn tls_args(&self, command: &mut Command) {
+        if let Some(ca_file) = &self.ca_file {
+            command.arg("--ca-file").arg(ca_file.path());
+        }
+
+        if self.insecure_skip_tls_verify {
+            command.arg("--insecure-skip-tls-verify");
+        }
+    }
 }
 
 #[derive(PartialEq)]
@@ -45,38 +133,213 @@ pub struct ChartInfo {
     pub name: String,
     pub namespace: String,
     pub app_version: String,
+    pub revision: String,
     pub status: String,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct ChartSearch {
     pub name: String,
+    pub version: String,
     pub app_version: String,
 }
 
+impl ChartSearch {
+    /// Select the highest chart version matching the constraint.
+    pub fn highest_matching(
+        constraint: &VersionReq,
+        found: impl IntoIterator<Item = Self>,
+    ) -> Option<Self> {
+        found
+            .into_iter()
+            .filter_map(|search| {
+                let version = search.version.strip_prefix("v").unwrap_or(&search.version);
+                Version::parse(version)
+                    .ok()
+                    .map(|version| (version, search))
+            })
+            .filter(|(version, _)| constraint.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, search)| search)
+    }
+}
+
+/// ChartMetadata is a subset of `helm show chart` output.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ChartMetadata {
+    version: String,
+    app_version: String,
+}
+
 impl FleetChart {
-    pub fn add_repo(&self) -> RepoAddResult<Child> {
-        Ok(Command::new("helm")
-            .args(["repo", "add", "fleet", &self.repo])
-            .spawn()?)
+    /// Chart with the version pinned to the installed one, used to apply values without upgrading.
+    pub fn pinned(&self, version: String) -> Self {
+        Self {
+            version: Some(Install::Version(version)),
+            ..self.clone()
+        }
     }
 
-    pub fn update_repo(&self) -> RepoUpdateResult<Child> {
-        Ok(Command::new("helm")
-            .args(["repo", "update", "fleet"])
-            .spawn()?)
+    /// Hash of the user supplied values for both charts, `None` if no values are set.
+    pub fn values_hash(&self) -> Option<String> {
+        if self.values.is_none() && self.crd_values.is_none() {
+            return None;
+        }
+
+        let values = serde_json::to_vec(&(&self.values, &self.crd_values)).unwrap_or_default();
+        Some(format!("{:x}", Sha256::digest(values)))
     }
 
-    pub async fn search_repo(&self) -> RepoSearchResult<Vec<ChartSearch>> {
-        let result = Command::new("helm")
+    /// Pass values to helm on stdin, preferring `--reset-values` on upgrade
+    /// so the user supplied values are authoritative. Returns the values to write.
+    fn values_args(
+        &self,
+        command: &mut Command,
+        operation: &HelmOperation,
+        values: Option<&Value>,
+    ) -> io::Result<Option<Vec<u8>>> {
+        let Some(values) = values else {
+            if operation == &HelmOperation::Upgrade {
+                command.arg(match self.reset_values {
+                    true => "--reset-values",
+                    false => "--reuse-values",
+                });
+            }
+            return Ok(None);
+        };
+
+        if operation == &HelmOperation::Upgrade {
+            command.arg("--reset-values");
+        }
+        command.args(["--values", "-"]);
+        command.stdin(Stdio::piped());
+
+        Ok(Some(serde_json::to_vec(values).map_err(io::Error::other)?))
+    }
+
+    /// Spawn the helm command, writing the values to its stdin.
+    async fn spawn_with_values(mut command: Command, values: Option<Vec<u8>>) -> io::Result<Child> {
+        let mut child = command.spawn()?;
+        if let (Some(values), Some(mut stdin)) = (values, child.stdin.take()) {
+            stdin.write_all(&values).await?;
+        }
+
+        Ok(child)
+    }
+
+    /// Add the chart repository, or login into OCI registry if credentials are provided.
+    /// Returns `None` if no action is required for the repository.
+    pub async fn add_repo(&self) -> RepoAddResult<Option<Child>> {
+        let repo = &self.repo;
+        let mut add = Command::new("helm");
+        match repo.is_oci() {
+            true if repo.credentials.is_none() => return Ok(None),
+            true => {
+                add.args(["registry", "login", repo.registry_host()]);
+                if let Some(ca_file) = &repo.ca_file {
+                    add.arg("--ca-file").arg(ca_file.path());
+                }
+                if repo.insecure_skip_tls_verify {
+                    add.arg("--insecure");
+                }
+            }
+            false => {
+                add.args(["repo", "add", &repo.name, &repo.url, "--force-update"]);
+                repo.tls_args(&mut add);
+            }
+        }
+
+        // Password is passed via stdin to avoid exposing it in the process list
+        if let Some(credentials) = &repo.credentials {
+            add.args(["--username", &credentials.username, "--password-stdin"]);
+            add.stdin(Stdio::piped());
+        }
+
+        let mut child = add.spawn()?;
+        if let (Some(credentials), Some(mut stdin)) = (&repo.credentials, child.stdin.take()) {
+            stdin.write_all(credentials.password.as_bytes()).await?;
+        }
+
+        Ok(Some(child))
+    }
+
+    /// Update the chart repository index. OCI registries have no index, so `None` is returned.
+    pub fn update_repo(&self) -> RepoUpdateResult<Option<Child>> {
+        if self.repo.is_oci() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            Command::new("helm")
+                .args(["repo", "update", &self.repo.name])
+                .spawn()?,
+        ))
+    }
+
+    /// Lookup the latest available version of the chart in the repository.
+    pub async fn search_chart(&self, chart: &str) -> RepoSearchResult<Option<ChartSearch>> {
+        let reference = self.repo.chart(chart);
+        let mut search = Command::new("helm");
+        match self.repo.is_oci() {
+            true => {
+                search.args(["show", "chart", &reference]);
+                self.repo.tls_args(&mut search);
+            }
+            false => {
+                search.args(["search", "repo", &reference, "-o", "json"]);
+            }
+        }
+
+        let result = search
             .stdout(Stdio::piped())
-            .args(["search", "repo", "fleet", "-o", "json"])
+            .stderr(Stdio::piped())
             .spawn()?
             .wait_with_output()
             .await?;
+        let output = &String::from_utf8(result.stdout)?;
+
+        if self.repo.is_oci() {
+            if !result.status.success() {
+                // Missing chart is the only expected failure, others are reported with helm output
+                let error = String::from_utf8(result.stderr)?;
+                return match error.contains("not found") {
+                    true => Ok(None),
+                    false => Err(RepoSearchError::ChartShow(error.trim().to_string())),
+                };
+            }
+
+            let metadata: ChartMetadata = serde_yaml::from_str(output)?;
+            return Ok(Some(ChartSearch {
+                name: reference,
+                version: metadata.version,
+                app_version: metadata.app_version,
+            }));
+        }
 
+        let found: Vec<ChartSearch> = serde_json::from_str(output)?;
+        Ok(found.into_iter().find(|r| r.name == reference))
+    }
+
+    /// List all available versions of the chart. OCI registries do not support listing chart versions,
+    /// so the lookup is rejected instead of resolving against the latest version only.
+    pub async fn search_versions(&self, chart: &str) -> RepoSearchResult<Vec<ChartSearch>> {
+        if self.repo.is_oci() {
+            return Err(RepoSearchError::VersionsUnsupported(self.repo.url.clone()));
+        }
+
+        let reference = self.repo.chart(chart);
+        let result = Command::new("helm")
+            .args(["search", "repo", &reference, "--versions", "-o", "json"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?
+            .wait_with_output()
+            .await?;
         let output = &String::from_utf8(result.stdout)?;
-        Ok(serde_json::from_str(output)?)
+
+        let found: Vec<ChartSearch> = serde_json::from_str(output)?;
+        Ok(found.into_iter().filter(|r| r.name == reference).collect())
     }
 
     pub async fn get_metadata(chart: &str) -> MetadataGetResult<Option<ChartInfo>> {
@@ -99,10 +362,11 @@ impl FleetChart {
         Ok(infos.into_iter().find(|i| i.name == chart))
     }
 
-    pub fn fleet(&self, operation: &HelmOperation) -> FleetInstallResult<Child> {
+    pub async fn fleet(&self, operation: &HelmOperation) -> FleetInstallResult<Child> {
         let mut install = Command::new("helm");
 
-        install.args([&operation.to_string(), "fleet", "fleet/fleet"]);
+        install.args([&operation.to_string(), "fleet", &self.repo.chart("fleet")]);
+        self.repo.tls_args(&mut install);
         let oci = self.feature_gates.experimental_oci_storage;
         let helm_ops = self.feature_gates.experimental_oci_storage;
         install.args([
@@ -116,9 +380,7 @@ impl FleetChart {
             &format!("extraEnv[1].value={helm_ops}",),
         ]);
 
-        if operation == &HelmOperation::Upgrade {
-            install.arg("--reuse-values");
-        }
+        let values = self.values_args(&mut install, operation, self.values.as_ref())?;
 
         if self.create_namespace {
             install.arg("--create-namespace");
@@ -129,7 +391,8 @@ impl FleetChart {
         }
 
         match self.version.clone().unwrap_or_default() {
-            Install::FollowLatest(_) => {}
+            // Constraints are resolved to a specific version before install
+            Install::FollowLatest(_) | Install::Constraint(_) => {}
             Install::Version(version) => {
                 install.args(["--version", &version]);
             }
@@ -144,18 +407,53 @@ impl FleetChart {
             &format!("bootstrap.enabled={}", self.bootstrap_local_cluster),
         ]);
 
-        Ok(install.spawn()?)
+        Ok(Self::spawn_with_values(install, values).await?)
     }
 
-    pub fn fleet_crds(&self, operation: &HelmOperation) -> FleetCRDInstallResult<Child> {
-        let mut install = Command::new("helm");
+    /// Roll back the release to the given revision, waiting for the restored resources to become ready.
+    pub fn rollback(&self, release: &str, revision: &str) -> FleetRollbackResult<Child> {
+        let mut rollback = Command::new("helm");
+        rollback.args(["rollback", release, revision]);
+
+        if !self.namespace.is_empty() {
+            rollback.args(["--namespace", &self.namespace]);
+        }
 
-        install.args([&operation.to_string(), "fleet-crd", "fleet/fleet-crd"]);
+        if self.wait {
+            rollback.arg("--wait");
+        }
 
-        if operation == &HelmOperation::Upgrade {
-            install.arg("--reuse-values");
+        Ok(rollback.spawn()?)
+    }
+
+    /// Uninstall the release, waiting for the release resources to be deleted.
+    pub fn uninstall(&self, release: &str) -> FleetUninstallResult<Child> {
+        let mut uninstall = Command::new("helm");
+        uninstall.args(["uninstall", release]);
+
+        if !self.namespace.is_empty() {
+            uninstall.args(["--namespace", &self.namespace]);
         }
 
+        if self.wait {
+            uninstall.arg("--wait");
+        }
+
+        Ok(uninstall.spawn()?)
+    }
+
+    pub async fn fleet_crds(&self, operation: &HelmOperation) -> FleetCRDInstallResult<Child> {
+        let mut install = Command::new("helm");
+
+        install.args([
+            &operation.to_string(),
+            "fleet-crd",
+            &self.repo.chart("fleet-crd"),
+        ]);
+        self.repo.tls_args(&mut install);
+
+        let values = self.values_args(&mut install, operation, self.crd_values.as_ref())?;
+
         if self.create_namespace {
             install.arg("--create-namespace");
         }
@@ -165,7 +463,8 @@ impl FleetChart {
         }
 
         match self.version.clone().unwrap_or_default() {
-            Install::FollowLatest(_) => {}
+            // Constraints are resolved to a specific version before install
+            Install::FollowLatest(_) | Install::Constraint(_) => {}
             Install::Version(version) => {
                 install.args(["--version", &version]);
             }
@@ -175,6 +474,119 @@ impl FleetChart {
             install.arg("--wait");
         }
 
-        Ok(install.spawn()?)
+        Ok(Self::spawn_with_values(install, values).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::VersionReq;
+    use serde_json::json;
+    use tokio::process::Command;
+
+    use super::{
+        ChartSearch, FleetChart, HelmOperation, HelmRepository, RepoSearchError, TempFile,
+    };
+
+    #[test]
+    fn test_chart_reference() {
+        let repo = HelmRepository {
+            name: "fleet".into(),
+            url: "https://rancher.github.io/fleet-helm-charts/".into(),
+            ..Default::default()
+        };
+        assert!(!repo.is_oci());
+        assert_eq!(repo.chart("fleet-crd"), "fleet/fleet-crd");
+
+        let repo = HelmRepository {
+            name: "fleet".into(),
+            url: "oci://harbor.example.com/charts/".into(),
+            ..Default::default()
+        };
+        assert!(repo.is_oci());
+        assert_eq!(repo.chart("fleet"), "oci://harbor.example.com/charts/fleet");
+        assert_eq!(repo.registry_host(), "harbor.example.com");
+    }
+
+    #[test]
+    fn test_highest_matching() {
+        let found = [
+            "0.11.9",
+            "0.12.0",
+            "0.12.3",
+            "v0.12.4",
+            "0.13.0",
+            "0.12.5-rc.1",
+        ]
+        .map(|v| ChartSearch {
+            name: "fleet/fleet".into(),
+            version: v.into(),
+            app_version: v.into(),
+        });
+
+        let constraint = VersionReq::parse(">=0.12.0, <0.13.0").unwrap();
+        let resolved = ChartSearch::highest_matching(&constraint, found).unwrap();
+        assert_eq!(resolved.version, "v0.12.4");
+
+        let constraint = VersionReq::parse(">=0.14.0").unwrap();
+        assert!(ChartSearch::highest_matching(&constraint, []).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_versions_oci() {
+        let chart = FleetChart {
+            repo: HelmRepository {
+                url: "oci://harbor.example.com/charts".into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let err = chart.search_versions("fleet").await.unwrap_err();
+        assert!(matches!(err, RepoSearchError::VersionsUnsupported(_)));
+    }
+
+    #[test]
+    fn test_values_args() {
+        let chart = FleetChart::default();
+        let mut command = Command::new("helm");
+        let values = json!({"replicas": 2});
+        let stdin = chart
+            .values_args(&mut command, &HelmOperation::Upgrade, Some(&values))
+            .unwrap();
+        let args: Vec<_> = command.as_std().get_args().collect();
+        assert_eq!(args, ["--reset-values", "--values", "-"]);
+        assert_eq!(stdin.unwrap(), br#"{"replicas":2}"#);
+
+        let mut command = Command::new("helm");
+        let stdin = chart
+            .values_args(&mut command, &HelmOperation::Upgrade, None)
+            .unwrap();
+        let args: Vec<_> = command.as_std().get_args().collect();
+        assert_eq!(args, ["--reuse-values"]);
+        assert!(stdin.is_none());
+
+        // Previously set values were removed, so the release values are reset to the chart defaults
+        let chart = FleetChart {
+            reset_values: true,
+            ..Default::default()
+        };
+        let mut command = Command::new("helm");
+        let stdin = chart
+            .values_args(&mut command, &HelmOperation::Upgrade, None)
+            .unwrap();
+        let args: Vec<_> = command.as_std().get_args().collect();
+        assert_eq!(args, ["--reset-values"]);
+        assert!(stdin.is_none());
+    }
+
+    #[test]
+    fn test_temp_file() {
+        let path = std::env::temp_dir().join("caapf-test-ca.crt");
+        let file = TempFile::write(path.clone(), "ca").unwrap();
+        assert_eq!(std::fs::read_to_string(file.path()).unwrap(), "ca");
+
+        drop(file);
+        assert!(!path.exists());
     }
 }
@@ -26,6 +26,22 @@ pub enum FleetCRDInstallError {
     CRDInstall(#[from] io::Error),
 }
 
+pub type FleetRollbackResult<T> = std::result::Result<T, FleetRollbackError>;
+
+#[derive(Error, Debug)]
+pub enum FleetRollbackError {
+    #[error("Fleet rollback error: {0}")]
+    FleetRollback(#[from] io::Error),
+}
+
+pub type FleetUninstallResult<T> = std::result::Result<T, FleetUninstallError>;
+
+#[derive(Error, Debug)]
+pub enum FleetUninstallError {
+    #[error("Fleet uninstall error: {0}")]
+    FleetUninstall(#[from] io::Error),
+}
+
 pub type RepoAddResult<T> = std::result::Result<T, RepoAddError>;
 
 #[derive(Error, Debug)]
@@ -34,6 +50,17 @@ pub enum RepoAddError {
     RepoAdd(#[from] io::Error),
 }
 
+pub type RepoAuthResult<T> = std::result::Result<T, RepoAuthError>;
+
+#[derive(Error, Debug)]
+pub enum RepoAuthError {
+    #[error("Repository secret fetch error: {0}")]
+    SecretFetch(#[from] kube::Error),
+
+    #[error("Repository CA bundle write error: {0}")]
+    CAWrite(#[from] io::Error),
+}
+
 pub type RepoUpdateResult<T> = std::result::Result<T, RepoUpdateError>;
 
 #[derive(Error, Debug)]
@@ -54,6 +81,15 @@ pub enum RepoSearchError {
 
     #[error("Deserialize search error: {0}")]
     DeserializeInfoError(#[from] serde_json::Error),
+
+    #[error("Deserialize chart metadata error: {0}")]
+    DeserializeChartError(#[from] serde_yaml::Error),
+
+    #[error("Chart show error: {0}")]
+    ChartShow(String),
+
+    #[error("Version constraints are not supported for OCI repository `{0}`, set a specific version or followLatest")]
+    VersionsUnsupported(String),
 }
 
 pub type MetadataGetResult<T> = std::result::Result<T, MetadataGetError>;
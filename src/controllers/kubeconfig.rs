@@ -0,0 +1,614 @@
+use crate::api::capi_cluster::{Cluster, CLUSTER_NAME_LABEL};
+use crate::api::fleet_addon_config::ServiceAccountKubeconfig;
+
+use chrono::{DateTime, Utc};
+use k8s_openapi::api::authentication::v1::{TokenRequest, TokenRequestSpec};
+use k8s_openapi::api::core::v1::{Secret, ServiceAccount};
+use k8s_openapi::api::rbac::v1::{ClusterRole, ClusterRoleBinding, PolicyRule, RoleRef, Subject};
+use k8s_openapi::ByteString;
+use kube::api::{ObjectMeta, Patch, PatchParams, PostParams};
+use kube::config::{AuthInfo, KubeConfigOptions, Kubeconfig, NamedAuthInfo, NamedContext};
+use kube::{Api, Client, Config, Resource, ResourceExt};
+use serde_json::json;
+use sha2::{Digest as _, Sha256};
+use thiserror::Error;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::controller::Context;
+
+/// Name of the ServiceAccount, ClusterRole and ClusterRoleBinding created in the workload cluster
+pub static FLEET_SERVICE_ACCOUNT: &str = "fleet-addon-import";
+
+/// Annotation on the minted kubeconfig secret with the token expiration time
+pub static TOKEN_EXPIRATION_ANNOTATION: &str = "token-expiration.fleet.addons.cluster.x-k8s.io";
+
+/// Annotation on the Fleet cluster with the hash of the workload kubeconfig secret used by the agent
+pub static KUBECONFIG_HASH_ANNOTATION: &str = "kubeconfig-hash.fleet.addons.cluster.x-k8s.io";
+
+/// Annotation on the minted kubeconfig secret with the hash of the admin kubeconfig it was minted from
+pub static ADMIN_KUBECONFIG_HASH_ANNOTATION: &str =
+    "admin-kubeconfig-hash.fleet.addons.cluster.x-k8s.io";
+
+/// Key of the kubeconfig in CAPI and Fleet kubeconfig secrets
+static KUBECONFIG_KEY: &str = "value";
+
+static FLEET_CONTEXT: &str = "fleet";
+
+/// Verbs for the resources managed by Fleet when deploying the agent
+const MANAGE: &[&str] = &[
+    "get", "list", "watch", "create", "update", "patch", "delete",
+];
+
+pub type KubeconfigResult<T, E = KubeconfigError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum KubeconfigError {
+    #[error("Kubeconfig secret lookup error: {0}")]
+    SecretLookup(#[source] kube::Error),
+
+    #[error("Admin kubeconfig secret `{0}` is missing or has no `value` key")]
+    MissingKubeconfig(String),
+
+    #[error("Admin kubeconfig error: {0}")]
+    Kubeconfig(#[from] kube::config::KubeconfigError),
+
+    #[error("Workload cluster client error: {0}")]
+    Client(#[source] kube::Error),
+
+    #[error("Workload cluster RBAC setup error: {0}")]
+    Rbac(#[source] kube::Error),
+
+    #[error("Workload cluster RBAC cleanup error: {0}")]
+    RbacCleanup(#[source] kube::Error),
+
+    #[error("ServiceAccount token request error: {0}")]
+    TokenRequest(#[source] kube::Error),
+
+    #[error("Kubeconfig encoding error: {0}")]
+    Encode(#[from] serde_yaml::Error),
+
+    #[error("Kubeconfig decoding error: {0}")]
+    Decode(#[from] std::string::FromUtf8Error),
+
+    #[error("Token decoding error: {0}")]
+    Token(#[from] serde_json::Error),
+
+    #[error("Kubeconfig secret update error: {0}")]
+    SecretPatch(#[source] kube::Error),
+
+    #[error("Kubeconfig secret delete error: {0}")]
+    SecretDelete(#[source] kube::Error),
+}
+
+/// Ensure the Fleet kubeconfig secret holds a valid ServiceAccount token for the workload cluster,
+/// minting a new one when it is missing, close to expiration, or the admin kubeconfig changed.
+/// Returns the time until rotation.
+pub(crate) async fn sync_service_account_kubeconfig(
+    ctx: Arc<Context>,
+    cluster: &Cluster,
+    config: &ServiceAccountKubeconfig,
+    secret_name: &str,
+) -> KubeconfigResult<Duration> {
+    let namespace = cluster.namespace().unwrap_or_default();
+    let secrets: Api<Secret> = Api::namespaced(ctx.client.clone(), &namespace);
+    let lifetime = config.expiration_seconds();
+
+    let admin_secret = cluster.kubeconfig_secret_name();
+    let admin = secrets
+        .get_opt(&admin_secret)
+        .await
+        .map_err(KubeconfigError::SecretLookup)?
+        .ok_or_else(|| KubeconfigError::MissingKubeconfig(admin_secret.clone()))?;
+    let admin_hash = secret_hash(&admin);
+
+    // Admin kubeconfig rotation, like a CA or endpoint change, invalidates the minted kubeconfig
+    let existing = secrets
+        .get_metadata_opt(secret_name)
+        .await
+        .map_err(KubeconfigError::SecretLookup)?;
+    let annotations = existing.as_ref().map(|secret| secret.annotations());
+    let minted_from_admin = annotations
+        .and_then(|a| a.get(ADMIN_KUBECONFIG_HASH_ANNOTATION))
+        .is_some_and(|hash| *hash == admin_hash);
+    if let Some(rotate_in) = annotations
+        .and_then(|a| a.get(TOKEN_EXPIRATION_ANNOTATION))
+        .and_then(|expiration| DateTime::parse_from_rfc3339(expiration).ok())
+        .and_then(|expiration| rotate_in(expiration.to_utc(), lifetime))
+        .filter(|_| minted_from_admin)
+    {
+        return Ok(rotate_in);
+    }
+
+    let admin = admin_kubeconfig(admin, &admin_secret)?;
+    let client = workload_client(admin.clone()).await?;
+
+    let sa_namespace = config.namespace();
+    setup_service_account(client.clone(), &sa_namespace).await?;
+
+    let token = Api::<ServiceAccount>::namespaced(client, &sa_namespace)
+        .create_token_request(
+            FLEET_SERVICE_ACCOUNT,
+            &PostParams::default(),
+            &TokenRequest {
+                spec: TokenRequestSpec {
+                    expiration_seconds: Some(lifetime),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(KubeconfigError::TokenRequest)?;
+    let expiration = token
+        .status
+        .as_ref()
+        .map(|status| status.expiration_timestamp.0)
+        .unwrap_or_else(|| Utc::now() + chrono::Duration::seconds(lifetime));
+    let token = token.status.map(|status| status.token).unwrap_or_default();
+
+    let kubeconfig = serde_yaml::to_string(&scoped_kubeconfig(admin, token)?)?;
+    let secret = Secret {
+        metadata: ObjectMeta {
+            name: Some(secret_name.to_string()),
+            namespace: Some(namespace),
+            labels: Some(BTreeMap::from([(
+                CLUSTER_NAME_LABEL.to_string(),
+                cluster.name_any(),
+            )])),
+            annotations: Some(BTreeMap::from([
+                (
+                    TOKEN_EXPIRATION_ANNOTATION.to_string(),
+                    expiration.to_rfc3339(),
+                ),
+                (ADMIN_KUBECONFIG_HASH_ANNOTATION.to_string(), admin_hash),
+            ])),
+            owner_references: cluster.owner_ref(&()).map(|owner| vec![owner]),
+            ..Default::default()
+        },
+        data: Some(BTreeMap::from([(
+            KUBECONFIG_KEY.to_string(),
+            ByteString(kubeconfig.into_bytes()),
+        )])),
+        ..Default::default()
+    };
+
+    secrets
+        .patch(
+            secret_name,
+            &PatchParams::apply("addon-provider-fleet").force(),
+            &Patch::Apply(secret),
+        )
+        .await
+        .map_err(KubeconfigError::SecretPatch)?;
+
+    Ok(rotate_in(expiration, lifetime).unwrap_or_default())
+}
+
+/// Remove the minted kubeconfig secret, along with the ServiceAccount and its RBAC from the workload
+/// cluster. Used once the cluster is de-imported, or the ServiceAccount kubeconfig is disabled.
+pub(crate) async fn remove_service_account_kubeconfig(
+    ctx: Arc<Context>,
+    namespace: &str,
+    cluster_name: &str,
+) -> KubeconfigResult<()> {
+    let secrets: Api<Secret> = Api::namespaced(ctx.client.clone(), namespace);
+    let secret_name = format!("{cluster_name}-fleet-kubeconfig");
+    if secrets
+        .get_metadata_opt(&secret_name)
+        .await
+        .map_err(KubeconfigError::SecretLookup)?
+        .is_none()
+    {
+        return Ok(());
+    }
+
+    // Workload cluster resources are removed while the admin kubeconfig is still available
+    let admin_secret = format!("{cluster_name}-kubeconfig");
+    if let Some(admin) = secrets
+        .get_opt(&admin_secret)
+        .await
+        .map_err(KubeconfigError::SecretLookup)?
+    {
+        let client = workload_client(admin_kubeconfig(admin, &admin_secret)?).await?;
+        remove_service_account(client).await?;
+    }
+
+    match secrets.delete(&secret_name, &Default::default()).await {
+        Err(kube::Error::Api(e)) if e.code == 404 => Ok(()),
+        r => r.map(|_| ()).map_err(KubeconfigError::SecretDelete),
+    }
+}
+
+/// Hash of the secret content, used to detect the kubeconfig rotation.
+pub(crate) fn secret_hash(secret: &Secret) -> String {
+    let mut hasher = Sha256::new();
+    for (key, value) in secret.data.iter().flatten() {
+        hasher.update(key.as_bytes());
+        hasher.update(&value.0);
+    }
+    for (key, value) in secret.string_data.iter().flatten() {
+        hasher.update(key.as_bytes());
+        hasher.update(value.as_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// Time left until the token is rotated, after two thirds of its lifetime.
+/// Returns None once the token is due for rotation.
+fn rotate_in(expiration: DateTime<Utc>, lifetime: i64) -> Option<Duration> {
+    let rotate_at = expiration - chrono::Duration::seconds(lifetime / 3);
+    (rotate_at - Utc::now()).to_std().ok()
+}
+
+/// Admin kubeconfig stored in the CAPI kubeconfig secret.
+fn admin_kubeconfig(secret: Secret, secret_name: &str) -> KubeconfigResult<Kubeconfig> {
+    let admin = secret
+        .data
+        .and_then(|mut data| data.remove(KUBECONFIG_KEY))
+        .ok_or_else(|| KubeconfigError::MissingKubeconfig(secret_name.to_string()))?;
+    Ok(Kubeconfig::from_yaml(&String::from_utf8(admin.0)?)?)
+}
+
+async fn workload_client(kubeconfig: Kubeconfig) -> KubeconfigResult<Client> {
+    Client::try_from(
+        Config::from_custom_kubeconfig(kubeconfig, &KubeConfigOptions::default()).await?,
+    )
+    .map_err(KubeconfigError::Client)
+}
+
+/// Create the ServiceAccount for Fleet in the workload cluster, bound to the permissions required
+/// to deploy the Fleet agent. The agent RBAC is created by Fleet, which requires `bind` and `escalate`
+/// on roles, allowing the ServiceAccount to grant itself any permission in the workload cluster.
+/// The agent role grants all permissions, so restricting `bind` to it would not narrow the access.
+async fn setup_service_account(client: Client, namespace: &str) -> KubeconfigResult<()> {
+    let pp = PatchParams::apply("addon-provider-fleet").force();
+    let meta = ObjectMeta {
+        name: Some(FLEET_SERVICE_ACCOUNT.to_string()),
+        ..Default::default()
+    };
+
+    Api::<ServiceAccount>::namespaced(client.clone(), namespace)
+        .patch(
+            FLEET_SERVICE_ACCOUNT,
+            &pp,
+            &Patch::Apply(ServiceAccount {
+                metadata: ObjectMeta {
+                    namespace: Some(namespace.to_string()),
+                    ..meta.clone()
+                },
+                ..Default::default()
+            }),
+        )
+        .await
+        .map_err(KubeconfigError::Rbac)?;
+
+    let rule = |groups: &[&str], resources: &[&str], verbs: &[&str]| PolicyRule {
+        api_groups: Some(groups.iter().map(ToString::to_string).collect()),
+        resources: Some(resources.iter().map(ToString::to_string).collect()),
+        verbs: verbs.iter().map(ToString::to_string).collect(),
+        ..Default::default()
+    };
+
+    Api::<ClusterRole>::all(client.clone())
+        .patch(
+            FLEET_SERVICE_ACCOUNT,
+            &pp,
+            &Patch::Apply(ClusterRole {
+                metadata: meta.clone(),
+                rules: Some(vec![
+                    rule(
+                        &[""],
+                        &["namespaces"],
+                        &["get", "list", "watch", "create", "update", "patch"],
+                    ),
+                    rule(&[""], &["secrets", "configmaps", "serviceaccounts"], MANAGE),
+                    rule(&["apps"], &["deployments"], MANAGE),
+                    rule(&["networking.k8s.io"], &["networkpolicies"], MANAGE),
+                    rule(&["scheduling.k8s.io"], &["priorityclasses"], MANAGE),
+                    rule(
+                        &["rbac.authorization.k8s.io"],
+                        &["clusterrolebindings", "rolebindings"],
+                        MANAGE,
+                    ),
+                    rule(
+                        &["rbac.authorization.k8s.io"],
+                        &["clusterroles", "roles"],
+                        &[MANAGE, &["bind", "escalate"]].concat(),
+                    ),
+                ]),
+                ..Default::default()
+            }),
+        )
+        .await
+        .map_err(KubeconfigError::Rbac)?;
+
+    Api::<ClusterRoleBinding>::all(client)
+        .patch(
+            FLEET_SERVICE_ACCOUNT,
+            &pp,
+            &Patch::Apply(ClusterRoleBinding {
+                metadata: meta,
+                role_ref: RoleRef {
+                    api_group: "rbac.authorization.k8s.io".into(),
+                    kind: "ClusterRole".into(),
+                    name: FLEET_SERVICE_ACCOUNT.into(),
+                },
+                subjects: Some(vec![Subject {
+                    kind: "ServiceAccount".into(),
+                    name: FLEET_SERVICE_ACCOUNT.into(),
+                    namespace: Some(namespace.to_string()),
+                    ..Default::default()
+                }]),
+            }),
+        )
+        .await
+        .map_err(KubeconfigError::Rbac)?;
+
+    Ok(())
+}
+
+/// Delete the ServiceAccount and its RBAC from the workload cluster. The ServiceAccount namespace
+/// is taken from the binding, as the setting may no longer be present.
+async fn remove_service_account(client: Client) -> KubeconfigResult<()> {
+    fn ignore_missing<T>(r: kube::Result<T>) -> KubeconfigResult<()> {
+        match r {
+            Err(kube::Error::Api(e)) if e.code == 404 => Ok(()),
+            r => r.map(|_| ()).map_err(KubeconfigError::RbacCleanup),
+        }
+    }
+
+    let bindings = Api::<ClusterRoleBinding>::all(client.clone());
+    let binding = bindings
+        .get_opt(FLEET_SERVICE_ACCOUNT)
+        .await
+        .map_err(KubeconfigError::RbacCleanup)?;
+    let subjects = binding.and_then(|binding| binding.subjects);
+    for namespace in subjects
+        .iter()
+        .flatten()
+        .filter_map(|s| s.namespace.as_ref())
+    {
+        ignore_missing(
+            Api::<ServiceAccount>::namespaced(client.clone(), namespace)
+                .delete(FLEET_SERVICE_ACCOUNT, &Default::default())
+                .await,
+        )?;
+    }
+
+    ignore_missing(
+        bindings
+            .delete(FLEET_SERVICE_ACCOUNT, &Default::default())
+            .await,
+    )?;
+    ignore_missing(
+        Api::<ClusterRole>::all(client)
+            .delete(FLEET_SERVICE_ACCOUNT, &Default::default())
+            .await,
+    )
+}
+
+/// Kubeconfig for the admin kubeconfig current cluster, authenticating with the token.
+fn scoped_kubeconfig(admin: Kubeconfig, token: String) -> KubeconfigResult<Kubeconfig> {
+    let cluster = admin
+        .current_context
+        .as_ref()
+        .and_then(|current| admin.contexts.iter().find(|c| &c.name == current))
+        .and_then(|context| context.context.as_ref())
+        .map(|context| context.cluster.clone());
+    let clusters = admin
+        .clusters
+        .into_iter()
+        .filter(|c| cluster.is_none() || Some(&c.name) == cluster.as_ref())
+        .take(1)
+        .collect::<Vec<_>>();
+    let cluster = clusters.first().map(|c| c.name.clone()).unwrap_or_default();
+    let auth_info: AuthInfo = serde_json::from_value(json!({ "token": token }))?;
+
+    Ok(Kubeconfig {
+        clusters,
+        auth_infos: vec![NamedAuthInfo {
+            name: FLEET_CONTEXT.into(),
+            auth_info: Some(auth_info),
+        }],
+        contexts: vec![NamedContext {
+            name: FLEET_CONTEXT.into(),
+            context: Some(kube::config::Context {
+                cluster,
+                user: Some(FLEET_CONTEXT.into()),
+                ..Default::default()
+            }),
+        }],
+        current_context: Some(FLEET_CONTEXT.into()),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use kube::config::Kubeconfig;
+
+    use http::Method;
+    use k8s_openapi::api::core::v1::Secret;
+    use k8s_openapi::ByteString;
+    use serde_json::json;
+
+    use crate::api::capi_cluster::Cluster;
+    use crate::fixtures::mock_context;
+
+    use super::{
+        remove_service_account, remove_service_account_kubeconfig, rotate_in, scoped_kubeconfig,
+        secret_hash, sync_service_account_kubeconfig, ADMIN_KUBECONFIG_HASH_ANNOTATION,
+        TOKEN_EXPIRATION_ANNOTATION,
+    };
+
+    #[test]
+    fn test_scoped_kubeconfig() {
+        let admin = Kubeconfig::from_yaml(
+            r#"
+apiVersion: v1
+kind: Config
+clusters:
+- name: other
+  cluster:
+    server: https://other:6443
+- name: workload
+  cluster:
+    server: https://workload:6443
+contexts:
+- name: admin@workload
+  context:
+    cluster: workload
+    user: admin
+current-context: admin@workload
+users:
+- name: admin
+  user:
+    client-certificate-data: Y2VydA==
+"#,
+        )
+        .unwrap();
+
+        let scoped = scoped_kubeconfig(admin, "token".into()).unwrap();
+        assert_eq!(1, scoped.clusters.len());
+        assert_eq!("workload", scoped.clusters[0].name);
+        assert_eq!(Some("fleet".to_string()), scoped.current_context);
+        let yaml = serde_yaml::to_string(&scoped).unwrap();
+        assert!(yaml.contains("token: token"));
+        assert!(!yaml.contains("client-certificate-data"));
+    }
+
+    #[test]
+    fn test_rotate_in() {
+        let lifetime = 3600;
+        let fresh = Utc::now() + chrono::Duration::seconds(lifetime);
+        assert!(rotate_in(fresh, lifetime).is_some());
+
+        let expiring = Utc::now() + chrono::Duration::seconds(lifetime / 4);
+        assert!(rotate_in(expiring, lifetime).is_none());
+    }
+
+    #[test]
+    fn test_secret_hash() {
+        let secret = |value: &str| Secret {
+            data: Some([("value".to_string(), ByteString(value.into()))].into()),
+            ..Default::default()
+        };
+
+        assert_eq!(secret_hash(&secret("a")), secret_hash(&secret("a")));
+        assert_ne!(secret_hash(&secret("a")), secret_hash(&secret("b")));
+        assert_eq!(secret_hash(&secret("a")).len(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_sync_service_account_kubeconfig_admin_rotation() {
+        let admin: Secret = serde_json::from_value(json!({
+            "metadata": {"name": "cluster-kubeconfig", "namespace": "default"},
+            "data": {"value": "YWRtaW4="},
+        }))
+        .unwrap();
+        let cluster: Cluster = serde_json::from_value(json!({
+            "metadata": {"name": "cluster", "namespace": "default"},
+            "spec": {},
+        }))
+        .unwrap();
+        let expiration = (Utc::now() + chrono::Duration::hours(24)).to_rfc3339();
+
+        for (admin_hash, minted) in [(secret_hash(&admin), false), ("rotated".into(), true)] {
+            let (served, expiration) = (admin.clone(), expiration.clone());
+            let (ctx, _) = mock_context(move |request| match request.path.as_str() {
+                "/api/v1/namespaces/default/secrets/cluster-kubeconfig" => {
+                    Some(serde_json::to_value(&served).unwrap())
+                }
+                "/api/v1/namespaces/default/secrets/cluster-fleet-kubeconfig" => Some(json!({
+                    "apiVersion": "v1",
+                    "kind": "Secret",
+                    "metadata": {
+                        "name": "cluster-fleet-kubeconfig",
+                        "namespace": "default",
+                        "annotations": {
+                            TOKEN_EXPIRATION_ANNOTATION: expiration,
+                            ADMIN_KUBECONFIG_HASH_ANNOTATION: admin_hash,
+                        },
+                    },
+                })),
+                _ => None,
+            });
+
+            // Valid token minted from the current admin kubeconfig is kept, while an admin
+            // kubeconfig change re-mints it, failing here on the invalid admin kubeconfig
+            let result = sync_service_account_kubeconfig(
+                ctx,
+                &cluster,
+                &Default::default(),
+                "cluster-fleet-kubeconfig",
+            )
+            .await;
+            assert_eq!(result.is_err(), minted);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remove_service_account() {
+        let (ctx, server) = mock_context(|request| match request.path.as_str() {
+            "/apis/rbac.authorization.k8s.io/v1/clusterrolebindings/fleet-addon-import" => {
+                Some(json!({
+                    "apiVersion": "rbac.authorization.k8s.io/v1",
+                    "kind": "ClusterRoleBinding",
+                    "metadata": {"name": "fleet-addon-import"},
+                    "roleRef": {
+                        "apiGroup": "rbac.authorization.k8s.io",
+                        "kind": "ClusterRole",
+                        "name": "fleet-addon-import",
+                    },
+                    "subjects": [{
+                        "kind": "ServiceAccount",
+                        "name": "fleet-addon-import",
+                        "namespace": "fleet-import",
+                    }],
+                }))
+            }
+            _ => None,
+        });
+
+        // ServiceAccount is removed from the namespace it was bound in, missing resources are skipped
+        remove_service_account(ctx.client.clone()).await.unwrap();
+        let deleted = |path: &str| server.requests(Method::DELETE, path).len();
+        assert_eq!(
+            deleted("/api/v1/namespaces/fleet-import/serviceaccounts/fleet-addon-import"),
+            1
+        );
+        assert_eq!(
+            deleted("/apis/rbac.authorization.k8s.io/v1/clusterrolebindings/fleet-addon-import"),
+            1
+        );
+        assert_eq!(
+            deleted("/apis/rbac.authorization.k8s.io/v1/clusterroles/fleet-addon-import"),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_service_account_kubeconfig() {
+        let secret = "/api/v1/namespaces/default/secrets/cluster-fleet-kubeconfig";
+        let (ctx, server) = mock_context(move |request| {
+            (request.path == secret).then(|| {
+                json!({
+                    "apiVersion": "v1",
+                    "kind": "Secret",
+                    "metadata": {"name": "cluster-fleet-kubeconfig", "namespace": "default"},
+                })
+            })
+        });
+
+        // Minted secret is removed even when the admin kubeconfig is gone
+        remove_service_account_kubeconfig(ctx, "default", "cluster")
+            .await
+            .unwrap();
+        assert_eq!(server.requests(Method::DELETE, secret).len(), 1);
+    }
+}
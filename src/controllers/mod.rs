@@ -1,3 +1,5 @@
+use kube::runtime::reflector::store::WriterDropped;
+use kubeconfig::KubeconfigError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -16,6 +18,29 @@ pub enum SyncError {
 
     #[error("BundleNamespaceMapping delete error: {0}")]
     BundleNsMappingDelete(#[from] kube::Error),
+
+    #[error("Cluster drain error: {0}")]
+    Drain(#[from] DrainError),
+}
+
+pub type DrainResult<T, E = DrainError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum DrainError {
+    #[error("Fleet cluster delete error: {0}")]
+    FleetDelete(#[source] kube::Error),
+
+    #[error("Fleet cluster lookup error: {0}")]
+    FleetLookup(#[source] kube::Error),
+
+    #[error("BundleDeployment lookup error: {0}")]
+    Lookup(#[source] kube::Error),
+
+    #[error("{0}")]
+    Event(#[from] BundleError),
+
+    #[error("Drain condition update error: {0}")]
+    Condition(#[source] kube::Error),
 }
 
 pub type ClusterSyncResult<T, E = ClusterSyncError> = std::result::Result<T, E>;
@@ -31,6 +56,9 @@ pub enum ClusterSyncError {
     #[error("Cluster group update error: {0}")]
     GroupPatchError(#[source] PatchError),
 
+    #[error("Kubernetes version group cleanup error: {0}")]
+    VersionGroupCleanup(#[source] kube::Error),
+
     #[error("Cluster BundleNamespaceMapping update error: {0}")]
     BundleNamespaceMappingError(#[source] PatchError),
 
@@ -39,6 +67,9 @@ pub enum ClusterSyncError {
 
     #[error("Cluster json encoding error: {0}")]
     ClusterEncodeError(#[from] serde_json::Error),
+
+    #[error("Fleet kubeconfig error: {0}")]
+    Kubeconfig(#[from] KubeconfigError),
 }
 
 pub type GroupSyncResult<T, E = GroupSyncError> = std::result::Result<T, E>;
@@ -53,6 +84,9 @@ pub enum GroupSyncError {
 
     #[error("Unable to find origin ClusterClass for the ClusterGroup: {0}")]
     ClassLookup(#[from] kube::Error),
+
+    #[error("{0}")]
+    Config(#[from] ConfigFetchError),
 }
 
 pub type GetOrCreateResult<T, E = GetOrCreateError> = std::result::Result<T, E>;
@@ -87,6 +121,9 @@ pub enum PatchError {
     #[error("Patch error: {0}")]
     Patch(#[source] kube::Error),
 
+    #[error("Dry-run comparison error: {0}")]
+    Encode(#[from] serde_json::Error),
+
     #[error("Diagnostics error: {0}")]
     Event(#[from] kube::Error),
 }
@@ -103,6 +140,53 @@ pub enum BundleError {
 
     #[error("BundleNamespaceMapping creating error: {0}")]
     Mapping(#[from] BundleMappingError),
+
+    #[error("Agent overrides lookup error: {0}")]
+    AgentOverrides(#[source] kube::Error),
+
+    #[error("Name collision lookup error: {0}")]
+    NameLookup(#[source] kube::Error),
+
+    #[error("Event publish error: {0}")]
+    Event(#[source] kube::Error),
+
+    #[error("Readiness gate check error: {0}")]
+    Readiness(#[source] kube::Error),
+
+    #[error("Fleet cluster pause error: {0}")]
+    Pause(#[source] kube::Error),
+
+    #[error("Fleet cluster adoption error: {0}")]
+    Adoption(#[source] kube::Error),
+
+    #[error("Kubeconfig rotation check error: {0}")]
+    KubeconfigRotation(#[source] kube::Error),
+
+    #[error("Cluster condition update error: {0}")]
+    Condition(#[source] kube::Error),
+}
+
+pub type DeimportResult<T, E = DeimportError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum DeimportError {
+    #[error("{0}")]
+    Config(#[from] ConfigFetchError),
+
+    #[error("Label Check error: {0}")]
+    LabelCheck(#[from] LabelCheckError),
+
+    #[error("Fleet resources cleanup error: {0}")]
+    Cleanup(#[source] kube::Error),
+
+    #[error("Finalizer release error: {0}")]
+    Finalizer(#[source] kube::Error),
+
+    #[error("ServiceAccount kubeconfig cleanup error: {0}")]
+    Kubeconfig(#[from] KubeconfigError),
+
+    #[error("Event publish error: {0}")]
+    Event(#[source] kube::Error),
 }
 
 #[derive(Error, Debug)]
@@ -117,11 +201,36 @@ pub type ConfigFetchResult<T> = std::result::Result<T, ConfigFetchError>;
 pub enum ConfigFetchError {
     #[error("Config lookup error: {0}")]
     Lookup(#[from] kube::Error),
+
+    #[error("Config store error: {0}")]
+    Store(#[from] WriterDropped),
 }
 
 pub mod addon_config;
+pub mod bundle_deployment;
 pub mod cluster;
 pub mod cluster_class;
 pub mod cluster_group;
 pub mod controller;
 pub mod helm;
+pub mod kubeconfig;
+
+pub type AddonStatusResult<T, E = AddonStatusError> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum AddonStatusError {
+    #[error("BundleDeployment lookup error: {0}")]
+    BundleDeploymentLookup(#[source] kube::Error),
+
+    #[error("Cluster status update error: {0}")]
+    ClusterStatusPatch(#[source] kube::Error),
+
+    #[error("Cluster lookup error: {0}")]
+    ClusterLookup(#[source] kube::Error),
+
+    #[error("Event publish error: {0}")]
+    Event(#[source] kube::Error),
+
+    #[error("{0}")]
+    Config(#[from] ConfigFetchError),
+}
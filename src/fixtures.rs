@@ -0,0 +1,141 @@
+//! Helper methods only available for tests
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use http::{Method, Request, Response, StatusCode};
+use kube::runtime::{reflector::store::Writer, watcher};
+use kube::{client::Body, Client};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+
+use crate::{
+    controllers::controller::Context,
+    multi_dispatcher::{BroadcastStream, MultiDispatcher},
+    Metrics,
+};
+
+/// Request received by the mocked API server.
+#[derive(Clone, Debug)]
+pub struct ApiRequest {
+    pub method: Method,
+    pub path: String,
+    pub query: String,
+    pub body: Value,
+}
+
+/// Requests recorded by the mocked API server, in the order of arrival.
+#[derive(Clone, Default)]
+pub struct ApiServer(Arc<Mutex<Vec<ApiRequest>>>);
+
+impl ApiServer {
+    /// Requests with the given method and path.
+    pub fn requests(&self, method: Method, path: &str) -> Vec<ApiRequest> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == method && r.path == path)
+            .cloned()
+            .collect()
+    }
+
+    /// Reasons of the published events.
+    pub fn events(&self) -> Vec<String> {
+        self.0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.method == Method::POST && r.path.ends_with("/events"))
+            .filter_map(|r| r.body["reason"].as_str().map(Into::into))
+            .collect()
+    }
+}
+
+/// Context with a client served by the handler. Requests without a handler response are answered
+/// with `404 Not Found`, while events are accepted as is.
+pub fn mock_context<F>(handler: F) -> (Arc<Context>, ApiServer)
+where
+    F: Fn(&ApiRequest) -> Option<Value> + Send + 'static,
+{
+    let (service, mut handle) = tower_test::mock::pair::<Request<Body>, Response<Body>>();
+    let server = ApiServer::default();
+    let recorded = server.clone();
+    tokio::spawn(async move {
+        while let Some((request, send)) = handle.next_request().await {
+            let (parts, body) = request.into_parts();
+            let body = body.collect_bytes().await.unwrap();
+            let request = ApiRequest {
+                method: parts.method,
+                path: parts.uri.path().to_string(),
+                query: parts.uri.query().unwrap_or_default().to_string(),
+                body: serde_json::from_slice(&body).unwrap_or_default(),
+            };
+            recorded.0.lock().unwrap().push(request.clone());
+
+            let response = match handler(&request) {
+                Some(response) => (StatusCode::OK, response),
+                None if request.method == Method::POST && request.path.ends_with("/events") => {
+                    (StatusCode::CREATED, request.body)
+                }
+                None => (
+                    StatusCode::NOT_FOUND,
+                    json!({
+                        "kind": "Status",
+                        "apiVersion": "v1",
+                        "status": "Failure",
+                        "message": "not found",
+                        "reason": "NotFound",
+                        "code": 404,
+                    }),
+                ),
+            };
+
+            send.send_response(
+                Response::builder()
+                    .status(response.0)
+                    .body(Body::from(serde_json::to_vec(&response.1).unwrap()))
+                    .unwrap(),
+            );
+        }
+    });
+
+    let ctx = Context {
+        client: Client::new(service, "default"),
+        diagnostics: Arc::new(RwLock::new(Default::default())),
+        metrics: Metrics::default(),
+        dispatcher: MultiDispatcher::new(128),
+        stream: BroadcastStream::new(Default::default()),
+        version: 32,
+        dry_run: false,
+        config: {
+            let mut writer = Writer::default();
+            writer.apply_watcher_event(&watcher::Event::InitDone);
+            writer.as_reader()
+        },
+    };
+
+    (Arc::new(ctx), server)
+}
+
+/// Context with the FleetAddonConfig in the config store.
+pub fn with_config(ctx: Arc<Context>, config: Value) -> Arc<Context> {
+    let mut writer = Writer::default();
+    writer.apply_watcher_event(&watcher::Event::InitDone);
+    writer.apply_watcher_event(&watcher::Event::Apply(
+        serde_json::from_value(config).unwrap(),
+    ));
+    Arc::new(Context {
+        config: writer.as_reader(),
+        ..ctx.deref().clone()
+    })
+}
+
+/// List response with the given items.
+pub fn list(items: Vec<Value>) -> Value {
+    json!({
+        "apiVersion": "v1",
+        "kind": "List",
+        "metadata": {"resourceVersion": "1"},
+        "items": items,
+    })
+}
@@ -0,0 +1,319 @@
+use std::{env, fs, future::Future, time::Duration};
+
+use chrono::Utc;
+use k8s_openapi::{
+    api::coordination::v1::{Lease, LeaseSpec},
+    apimachinery::pkg::apis::meta::v1::MicroTime,
+};
+use kube::{
+    api::{ObjectMeta, PostParams},
+    Api, Client,
+};
+use rand::distr::{Alphanumeric, SampleString as _};
+use thiserror::Error;
+use tokio::{signal, time::sleep};
+use tracing::{info, warn};
+
+static SERVICE_ACCOUNT_NAMESPACE: &str = "/var/run/secrets/kubernetes.io/serviceaccount/namespace";
+
+/// Time a lease is valid after the last renewal.
+const LEASE_DURATION: Duration = Duration::from_secs(15);
+/// Time the leader keeps retrying a failed renewal before stepping down.
+const RENEW_DEADLINE: Duration = Duration::from_secs(10);
+/// Interval between acquire and renew attempts.
+const RETRY_PERIOD: Duration = Duration::from_secs(2);
+
+pub type LeaderElectionResult<T> = std::result::Result<T, LeaderElectionError>;
+
+#[derive(Error, Debug)]
+pub enum LeaderElectionError {
+    #[error("Lease update error: {0}")]
+    Lease(#[from] kube::Error),
+
+    #[error("Leadership lost for lease `{0}`")]
+    LeadershipLost(String),
+}
+
+/// LeaderElector holds a `coordination.k8s.io/v1` Lease on behalf of the current process.
+///
+/// Only the holder of the lease is allowed to run reconcilers, while standby replicas
+/// keep retrying to acquire the lease once the current leader steps down or stops renewing.
+#[derive(Clone)]
+pub struct LeaderElector {
+    api: Api<Lease>,
+    name: String,
+    identity: String,
+}
+
+impl LeaderElector {
+    pub fn new(client: Client, namespace: Option<String>, name: &str) -> Self {
+        let namespace = namespace
+            .or_else(|| fs::read_to_string(SERVICE_ACCOUNT_NAMESPACE).ok())
+            .map(|ns| ns.trim().to_string())
+            .unwrap_or("default".into());
+
+        // Pod name is unique within the namespace, suffix protects from restarts within the lease duration
+        let hostname = env::var("HOSTNAME").unwrap_or("caapf".into());
+        let identity = format!(
+            "{hostname}_{}",
+            Alphanumeric.sample_string(&mut rand::rng(), 8)
+        );
+
+        Self {
+            api: Api::namespaced(client, &namespace),
+            name: name.into(),
+            identity,
+        }
+    }
+
+    /// Wait for leadership and run the future until it completes. The lease is released afterwards,
+    /// allowing a standby replica to take over without waiting for the lease to expire.
+    ///
+    /// Returns `None` if a shutdown signal was received. A signal received while leading cancels
+    /// the future, so the lease is released even if the future does not finish on its own.
+    pub async fn run<F: Future>(&self, fut: F) -> LeaderElectionResult<Option<F::Output>> {
+        self.run_until(fut, shutdown_signal()).await
+    }
+
+    async fn run_until<F: Future>(
+        &self,
+        fut: F,
+        shutdown: impl Future,
+    ) -> LeaderElectionResult<Option<F::Output>> {
+        tokio::pin!(shutdown);
+        tokio::select! {
+            _ = self.acquire() => {},
+            _ = &mut shutdown => {
+                info!("Received shutdown signal while waiting for leadership");
+                return Ok(None);
+            }
+        };
+
+        let result = tokio::select! {
+            output = fut => Ok(Some(output)),
+            lost = self.renew() => Err(lost),
+            _ = &mut shutdown => {
+                info!("Received shutdown signal, stepping down");
+                Ok(None)
+            }
+        };
+
+        if let Err(e) = self.release().await {
+            warn!("Failed to release lease {}: {e}", self.name);
+        }
+
+        result
+    }
+
+    async fn acquire(&self) {
+        info!(
+            "Attempting to acquire lease {} as {}",
+            self.name, self.identity
+        );
+        loop {
+            match self.try_acquire_or_renew().await {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to acquire lease {}: {e}", self.name),
+            }
+            sleep(RETRY_PERIOD).await;
+        }
+        info!("Acquired lease {} as {}", self.name, self.identity);
+    }
+
+    async fn renew(&self) -> LeaderElectionError {
+        let mut last_renew = Utc::now();
+        loop {
+            sleep(RETRY_PERIOD).await;
+            match self.try_acquire_or_renew().await {
+                Ok(true) => last_renew = Utc::now(),
+                Ok(false) => break,
+                Err(e) => {
+                    warn!("Failed to renew lease {}: {e}", self.name);
+                    if (Utc::now() - last_renew).to_std().unwrap_or_default() > RENEW_DEADLINE {
+                        break;
+                    }
+                }
+            }
+        }
+
+        warn!("Lost lease {}", self.name);
+        LeaderElectionError::LeadershipLost(self.name.clone())
+    }
+
+    async fn try_acquire_or_renew(&self) -> kube::Result<bool> {
+        let now = MicroTime(Utc::now());
+        let Some(mut lease) = self.api.get_opt(&self.name).await? else {
+            let lease = Lease {
+                metadata: ObjectMeta {
+                    name: Some(self.name.clone()),
+                    ..Default::default()
+                },
+                spec: Some(LeaseSpec {
+                    holder_identity: Some(self.identity.clone()),
+                    lease_duration_seconds: Some(LEASE_DURATION.as_secs() as i32),
+                    acquire_time: Some(now.clone()),
+                    renew_time: Some(now),
+                    lease_transitions: Some(0),
+                    ..Default::default()
+                }),
+            };
+
+            return match self.api.create(&PostParams::default(), &lease).await {
+                Ok(_) => Ok(true),
+                Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+                Err(e) => Err(e),
+            };
+        };
+
+        let spec = lease.spec.get_or_insert_default();
+        let held = spec.holder_identity.as_ref() == Some(&self.identity);
+        if !held && !Self::expired(spec) {
+            return Ok(false);
+        }
+
+        if !held {
+            spec.acquire_time = Some(now.clone());
+            spec.lease_transitions = Some(spec.lease_transitions.unwrap_or_default() + 1);
+        }
+        spec.holder_identity = Some(self.identity.clone());
+        spec.lease_duration_seconds = Some(LEASE_DURATION.as_secs() as i32);
+        spec.renew_time = Some(now);
+
+        // Replace is guarded by resourceVersion, so concurrent updates result in a conflict
+        match self
+            .api
+            .replace(&self.name, &PostParams::default(), &lease)
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(kube::Error::Api(e)) if e.code == 409 => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn release(&self) -> kube::Result<()> {
+        let Some(mut lease) = self.api.get_opt(&self.name).await? else {
+            return Ok(());
+        };
+
+        let spec = lease.spec.get_or_insert_default();
+        if spec.holder_identity.as_ref() != Some(&self.identity) {
+            return Ok(());
+        }
+
+        spec.holder_identity = None;
+        spec.lease_duration_seconds = Some(1);
+        spec.renew_time = Some(MicroTime(Utc::now()));
+
+        self.api
+            .replace(&self.name, &PostParams::default(), &lease)
+            .await?;
+
+        info!("Released lease {}", self.name);
+        Ok(())
+    }
+
+    fn expired(spec: &LeaseSpec) -> bool {
+        let (Some(holder), Some(renew_time)) = (&spec.holder_identity, &spec.renew_time) else {
+            return true;
+        };
+
+        let duration = spec
+            .lease_duration_seconds
+            .unwrap_or(LEASE_DURATION.as_secs() as i32);
+        holder.is_empty() || renew_time.0 + chrono::Duration::seconds(duration.into()) < Utc::now()
+    }
+}
+
+async fn shutdown_signal() {
+    let ctrl_c = signal::ctrl_c();
+    #[cfg(unix)]
+    {
+        let mut terminate = signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to register SIGTERM handler");
+        tokio::select! {
+            _ = ctrl_c => {},
+            _ = terminate.recv() => {},
+        }
+    }
+    #[cfg(not(unix))]
+    ctrl_c.await.expect("failed to register ctrl-c handler");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use http::Method;
+    use k8s_openapi::{
+        api::coordination::v1::LeaseSpec, apimachinery::pkg::apis::meta::v1::MicroTime,
+    };
+    use serde_json::Value;
+
+    use crate::fixtures::mock_context;
+
+    use super::LeaderElector;
+
+    const LEASES_PATH: &str = "/apis/coordination.k8s.io/v1/namespaces/default/leases";
+
+    #[test]
+    fn test_lease_expired() {
+        assert!(LeaderElector::expired(&LeaseSpec::default()));
+
+        assert!(!LeaderElector::expired(&LeaseSpec {
+            holder_identity: Some("other".into()),
+            lease_duration_seconds: Some(15),
+            renew_time: Some(MicroTime(Utc::now())),
+            ..Default::default()
+        }));
+
+        assert!(LeaderElector::expired(&LeaseSpec {
+            holder_identity: Some("other".into()),
+            lease_duration_seconds: Some(15),
+            renew_time: Some(MicroTime(Utc::now() - chrono::Duration::seconds(20))),
+            ..Default::default()
+        }));
+
+        assert!(LeaderElector::expired(&LeaseSpec {
+            holder_identity: Some("".into()),
+            renew_time: Some(MicroTime(Utc::now())),
+            ..Default::default()
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_release_on_shutdown() {
+        // Lease is served as last written
+        let lease = Arc::new(Mutex::new(None::<Value>));
+        let (ctx, server) = mock_context(move |request| {
+            let mut lease = lease.lock().unwrap();
+            if request.method != Method::GET {
+                *lease = Some(request.body.clone());
+            }
+            lease.clone()
+        });
+
+        let elector = LeaderElector::new(ctx.client.clone(), Some("default".into()), "caapf");
+        let acquired = server.clone();
+        let shutdown = async move {
+            while acquired.requests(Method::POST, LEASES_PATH).is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+
+        // Leader work which never finishes is cancelled and the lease is released
+        let result = elector
+            .run_until(std::future::pending::<()>(), shutdown)
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        let released = server.requests(Method::PUT, &format!("{LEASES_PATH}/caapf"));
+        assert_eq!(released.len(), 1);
+        assert!(released[0].body["spec"]["holderIdentity"].is_null());
+        assert_eq!(released[0].body["spec"]["leaseDurationSeconds"], 1);
+    }
+}
@@ -1,8 +1,11 @@
 use std::io;
 
 use controllers::{
-    addon_config::{AddonConfigSyncError, DynamicWatcherError, FleetPatchError},
-    helm, BundleError, SyncError,
+    addon_config::{
+        AddonConfigSyncError, ChartValuesError, DynamicWatcherError, FleetPatchError,
+        UninstallError,
+    },
+    helm, AddonStatusError, BundleError, DeimportError, SyncError,
 };
 use futures::channel::mpsc::TrySendError;
 use thiserror::Error;
@@ -21,15 +24,30 @@ pub enum Error {
     #[error("Fleet error: {0}")]
     FleetError(#[from] SyncError),
 
+    #[error("Fleet addon status error: {0}")]
+    AddonStatusError(#[from] AddonStatusError),
+
+    #[error("Cluster de-import error: {0}")]
+    Deimport(#[from] DeimportError),
+
     #[error("Fleet config error: {0}")]
     FleetConfigError(#[from] AddonConfigSyncError),
 
     #[error("Fleet chart patch error: {0}")]
     FleetChartPatchError(#[from] FleetPatchError),
 
+    #[error("Fleet uninstall error: {0}")]
+    FleetUninstall(#[from] UninstallError),
+
+    #[error("Fleet chart values error: {0}")]
+    ChartValues(#[from] ChartValuesError),
+
     #[error("Fleet repo add error: {0}")]
     RepoAdd(#[from] helm::RepoAddError),
 
+    #[error("Fleet repo auth error: {0}")]
+    RepoAuth(#[from] helm::RepoAuthError),
+
     #[error("Fleet repo update error: {0}")]
     RepoUpdate(#[from] helm::RepoUpdateError),
 
@@ -61,6 +79,7 @@ pub mod controller;
 pub use crate::controller::*;
 pub mod api;
 pub mod controllers;
+pub mod leader_election;
 mod multi_dispatcher;
 pub mod predicates;
 
@@ -71,6 +90,5 @@ pub mod telemetry;
 mod metrics;
 pub use metrics::Metrics;
 
-/*
-#[cfg(test)] pub mod fixtures;
-*/
+#[cfg(test)]
+pub mod fixtures;
@@ -1,9 +1,11 @@
 use actix_web::{
     get, middleware, web::Data, App, HttpRequest, HttpResponse, HttpServer, Responder,
 };
+use controller::leader_election::LeaderElector;
 pub use controller::{self, telemetry, State};
 use kube::Client;
 use prometheus::{Encoder, TextEncoder};
+use std::future::Future;
 
 #[get("/metrics")]
 async fn metrics(c: Data<State>, _req: HttpRequest) -> impl Responder {
@@ -44,16 +46,31 @@ async fn main() -> anyhow::Result<()> {
             .expect("version parse successfully"),
     );
 
+    let elector = state.flags.leader_elect.then(|| {
+        LeaderElector::new(
+            client.clone(),
+            state.flags.leader_election_namespace.clone(),
+            &state.flags.leader_election_id(),
+        )
+    });
+
     match state.flags.helm_install {
         true => {
             let helm_install_controller = controller::run_fleet_helm_controller(state.clone());
-            tokio::join!(helm_install_controller);
+            run_elected(elector.as_ref(), helm_install_controller).await?;
         }
         false => {
             let fleet_config_controller =
                 controller::run_fleet_addon_config_controller(state.clone());
             let cluster_controller = controller::run_cluster_controller(state.clone());
             let cluster_class_controller = controller::run_cluster_class_controller(state.clone());
+            let controllers = async {
+                tokio::join!(
+                    cluster_controller,
+                    cluster_class_controller,
+                    fleet_config_controller
+                )
+            };
 
             // Start web server
             let server = HttpServer::new(move || {
@@ -68,14 +85,27 @@ async fn main() -> anyhow::Result<()> {
             .shutdown_timeout(5)
             .run();
 
-            tokio::join!(
-                cluster_controller,
-                cluster_class_controller,
-                fleet_config_controller,
-                server
-            )
-            .3?;
+            // Web server keeps serving health and metrics while waiting for leadership
+            tokio::try_join!(run_elected(elector.as_ref(), controllers), async {
+                Ok(server.await?)
+            })?;
         }
     };
     Ok(())
 }
+
+/// Run controllers, only after acquiring the leadership if leader election is enabled
+async fn run_elected(
+    elector: Option<&LeaderElector>,
+    controllers: impl Future,
+) -> anyhow::Result<()> {
+    match elector {
+        Some(elector) => {
+            elector.run(controllers).await?;
+        }
+        None => {
+            controllers.await;
+        }
+    }
+    Ok(())
+}
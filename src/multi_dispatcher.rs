@@ -95,7 +95,7 @@ impl<W> Clone for BroadcastStream<W> {
 
 impl<W> BroadcastStream<W>
 where
-    W: Stream<Item = Result<Event<DynamicObject>>> + Unpin,
+    W: Stream + Unpin,
 {
     pub fn new(stream: Arc<Mutex<W>>) -> Self {
         Self { stream }
@@ -104,7 +104,7 @@ where
 
 impl<W> Stream for BroadcastStream<W>
 where
-    W: Stream<Item = Result<Event<DynamicObject>>> + Unpin,
+    W: Stream + Unpin,
 {
     type Item = W::Item;
 
@@ -1,9 +1,33 @@
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+
 use kube::runtime::predicates;
 use kube::ResourceExt;
 
+use crate::api::fleet_addon_config::FleetAddonConfig;
+
 pub fn generation_with_deletion(obj: &impl ResourceExt) -> Option<u64> {
     match obj.meta().deletion_timestamp {
         Some(_) => predicates::resource_version(obj),
         None => predicates::generation(obj),
     }
 }
+
+/// Hash of the FleetAddonConfig settings deciding which clusters are de-imported: the import
+/// selectors, the de-import policy and the dry-run mode.
+pub fn deimport_settings(config: &FleetAddonConfig) -> Option<u64> {
+    let selectors: Vec<_> = config
+        .import_profiles()
+        .into_iter()
+        .map(|(name, cluster)| (name, &cluster.selectors))
+        .collect();
+    let settings = serde_json::to_string(&(
+        selectors,
+        config.deimport_policy(),
+        config.dry_run_enabled(),
+    ))
+    .ok()?;
+
+    let mut hasher = DefaultHasher::new();
+    settings.hash(&mut hasher);
+    Some(hasher.finish())
+}